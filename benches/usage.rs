@@ -1,12 +1,23 @@
 use criterion::*;
 
 use prodash::{messages::MessageLevel, Tree, TreeOptions};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Barrier,
+    },
+    time::Instant,
+};
+
+const NUM_THREADS: usize = 4;
+const INCREMENTS_PER_THREAD: usize = 10_000;
 
 fn usage(c: &mut Criterion) {
     fn small_tree() -> Tree {
         TreeOptions {
             initial_capacity: 10,
             message_buffer_capacity: 2,
+            ..TreeOptions::default()
         }
         .create()
     };
@@ -35,6 +46,59 @@ fn usage(c: &mut Criterion) {
                 progress.set(5);
             });
         });
+    c.benchmark_group("tree::Item::inc_by")
+        .throughput(Throughput::Elements((NUM_THREADS * INCREMENTS_PER_THREAD) as u64))
+        .bench_function(
+            "increment one task per thread concurrently while the tree is snapshotted continuously",
+            |b| {
+                let root = small_tree();
+                let stop = Arc::new(AtomicBool::new(false));
+                let snapshotter = std::thread::spawn({
+                    let root = root.clone();
+                    let stop = stop.clone();
+                    move || {
+                        let mut out = Vec::new();
+                        while !stop.load(Ordering::Relaxed) {
+                            root.sorted_snapshot(&mut out);
+                        }
+                    }
+                });
+                // Workers are spawned once and run one round of increments per iteration, which is all that is measured.
+                let start = Arc::new(Barrier::new(NUM_THREADS + 1));
+                let done = Arc::new(Barrier::new(NUM_THREADS + 1));
+                let workers: Vec<_> = (0..NUM_THREADS)
+                    .map(|id| {
+                        let mut progress = root.add_child(format!("reader {}", id));
+                        progress.init(None, Some("bytes".into()));
+                        let (start, done, stop) = (start.clone(), done.clone(), stop.clone());
+                        std::thread::spawn(move || loop {
+                            start.wait();
+                            if stop.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            for _ in 0..INCREMENTS_PER_THREAD {
+                                progress.inc_by(512);
+                            }
+                            done.wait();
+                        })
+                    })
+                    .collect();
+                b.iter_custom(|iterations| {
+                    let started_at = Instant::now();
+                    for _ in 0..iterations {
+                        start.wait();
+                        done.wait();
+                    }
+                    started_at.elapsed()
+                });
+                stop.store(true, Ordering::Relaxed);
+                start.wait();
+                for worker in workers {
+                    worker.join().expect("no panic");
+                }
+                snapshotter.join().expect("no panic");
+            },
+        );
     c.benchmark_group("tree::Item::message")
         .throughput(Throughput::Elements(1))
         .bench_function(
@@ -53,7 +53,7 @@ async fn work_forever(mut args: args::Options) -> Result {
 
     loop {
         let local_work = new_chunk_of_work(
-            NestingLevel(thread_rng().gen_range(0, Key::max_level())),
+            NestingLevel(thread_rng().gen_range(0, MAX_NESTING_LEVEL)),
             progress.clone(),
             speed,
             changing_names,
@@ -66,7 +66,7 @@ async fn work_forever(mut args: args::Options) -> Result {
         };
         let pooled_work = (0..num_chunks).map(|_| {
             smol::Task::spawn(new_chunk_of_work(
-                NestingLevel(thread_rng().gen_range(0, Key::max_level())),
+                NestingLevel(thread_rng().gen_range(0, MAX_NESTING_LEVEL)),
                 progress.clone(),
                 speed,
                 changing_names,
@@ -111,6 +111,11 @@ async fn work_item(mut progress: Item, speed: f32, changing_names: bool) {
     );
 
     for step in 0..max {
+        if progress.is_cancelled() {
+            progress.cancelled();
+            progress.info("cancelled on request");
+            return;
+        }
         progress.set(step as Step);
         let delay_ms = if thread_rng().gen_bool(CHANCE_TO_BLOCK_PER_STEP) {
             let eta = if thread_rng().gen_bool(CHANCE_TO_SHOW_ETA) {
@@ -186,11 +191,7 @@ struct NestingLevel(u8);
 type Result = std::result::Result<(), Box<dyn Error + Send>>;
 
 use futures_util::{future::join_all, future::Either, FutureExt};
-use prodash::{
-    progress::{Key, Step},
-    tree::Item,
-    Tree,
-};
+use prodash::{progress::Step, tree::Item, Tree};
 use rand::prelude::*;
 use std::{
     error::Error,
@@ -199,6 +200,7 @@ use std::{
 };
 
 const WORK_STEPS_NEEDED_FOR_UNBOUNDED_TASK: u8 = 100;
+const MAX_NESTING_LEVEL: u8 = 6;
 const UNITS: &[&str] = &["Mb", "kb", "items", "files"];
 const REASONS: &[&str] = &["due to star alignment", "IO takes time", "仪表板演示", "just because"];
 const WORK_NAMES: &[&str] = &[
@@ -49,9 +49,11 @@ pub fn launch_ambient_gui(
                         args.line_start.unwrap_or(1),
                         args.line_end.unwrap_or(2),
                     )),
+                    min_message_level: None,
                     initial_delay: args.line_initial_delay.map(Duration::from_secs_f32),
                     frames_per_second: args.fps,
                     keep_running_if_progress_is_empty: true,
+                    max_idle_redraw_interval: Some(Duration::from_secs(1)),
                     throughput,
                 },
             );
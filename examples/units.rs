@@ -38,7 +38,7 @@ fn work_for_a_long_time_blocking(root: Tree) {
         Some(100_000_000),
         Some(unit::dynamic_and_mode(
             unit::Bytes,
-            unit::display::Mode::with_percentage().and_throughput(),
+            unit::display::Mode::with_percentage().and_throughput().and_eta(),
         )),
     );
 
@@ -71,7 +71,7 @@ fn work_for_a_long_time_blocking(root: Tree) {
         Some(7_542_241),
         Some(unit::dynamic_and_mode(
             unit::Human::new(formatter(2), "items"),
-            unit::display::Mode::with_percentage().and_throughput(),
+            unit::display::Mode::with_percentage().and_throughput().and_eta(),
         )),
     );
 
@@ -88,7 +88,7 @@ fn work_for_a_long_time_blocking(root: Tree) {
         Some(100),
         Some(unit::dynamic_and_mode(
             unit::Range::new("steps"),
-            unit::display::Mode::with_percentage().and_throughput(),
+            unit::display::Mode::with_percentage().and_throughput().and_eta(),
         )),
     );
 
@@ -0,0 +1,168 @@
+use crate::{child::Call, child::ENV_VAR, Progress};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read},
+    net::{SocketAddr, TcpListener, TcpStream},
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The maximum amount of connections served at the same time, beyond which new ones are closed right away.
+const MAX_CONNECTIONS: usize = 64;
+/// The time a connection has to send the token before it is closed.
+const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shows the progress of child processes under a [`Progress`](../trait.Progress.html) of the parent process,
+/// accepting connections of [`Reporter`s](./struct.Reporter.html) from a background thread until it is dropped.
+///
+/// All tasks of a child process are removed once it disconnects, usually when it exits.
+///
+/// Only connections sending the random token handed to children along with the address are served,
+/// and at most 64 of them at a time.
+pub struct Graft {
+    address: SocketAddr,
+    token: String,
+    stop: Arc<AtomicBool>,
+}
+
+/// Initialization
+impl Graft {
+    /// Show the progress of all child processes configured with this instance under `target`.
+    ///
+    /// The progress of `target` itself can be set by the children as well.
+    pub fn new<P>(target: P) -> io::Result<Graft>
+    where
+        P: Progress<SubProgress = P>,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let token = new_token();
+        let target = Arc::new(Mutex::new(target));
+        let connections = Arc::new(AtomicUsize::new(0));
+        std::thread::spawn({
+            let stop = stop.clone();
+            let token = token.clone();
+            move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // Only this thread adds connections, so the limit can't be exceeded.
+                        if connections.load(Ordering::SeqCst) >= MAX_CONNECTIONS {
+                            continue;
+                        }
+                        connections.fetch_add(1, Ordering::SeqCst);
+                        let target = target.clone();
+                        let token = token.clone();
+                        let connections = connections.clone();
+                        std::thread::spawn(move || {
+                            // Children going away or sending garbage only affect their own tasks.
+                            serve(&target, &token, stream).ok();
+                            connections.fetch_sub(1, Ordering::SeqCst);
+                        });
+                    }
+                }
+            }
+        });
+        Ok(Graft { address, token, stop })
+    }
+}
+
+impl Graft {
+    /// Set the environment variable telling the child process spawned by `command` where to report its progress to.
+    pub fn configure<'a>(&self, command: &'a mut Command) -> &'a mut Command {
+        command.env(ENV_VAR, format!("{}@{}", self.token, self.address))
+    }
+}
+
+impl Drop for Graft {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // wake up the thread accepting connections so it sees it should stop
+        TcpStream::connect(self.address).ok();
+    }
+}
+
+/// Returns a random token for children to authenticate with, derived from the hasher seeds the operating system provides.
+fn new_token() -> String {
+    use std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hasher},
+    };
+    let random = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", random(), random())
+}
+
+/// Apply all calls of the child connected through `stream` to `target` once it sent the `token`.
+fn serve<P>(target: &Mutex<P>, token: &str, stream: TcpStream) -> io::Result<()>
+where
+    P: Progress<SubProgress = P>,
+{
+    stream.set_read_timeout(Some(AUTHENTICATION_TIMEOUT))?;
+    let mut input = BufReader::new(stream);
+    let mut line = String::new();
+    (&mut input).take(token.len() as u64 + 1).read_line(&mut line)?;
+    if line.strip_suffix('\n') != Some(token) {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "invalid token"));
+    }
+    input.get_ref().set_read_timeout(None)?;
+    apply_calls(target, input)
+}
+
+/// Apply all calls read from `input` to `target` or the tasks created underneath it, until the child disconnects.
+fn apply_calls<P>(target: &Mutex<P>, input: impl BufRead) -> io::Result<()>
+where
+    P: Progress<SubProgress = P>,
+{
+    let mut tasks = HashMap::<u64, P>::new();
+    for line in input.lines() {
+        let call: Call = serde_json::from_str(&line?)?;
+        let id = match &call {
+            Call::AddChild { parent, .. } => *parent,
+            Call::Init { id, .. }
+            | Call::Set { id, .. }
+            | Call::IncBy { id, .. }
+            | Call::SetName { id, .. }
+            | Call::Message { id, .. }
+            | Call::SetAttribute { id, .. }
+            | Call::Finished { id }
+            | Call::Failed { id }
+            | Call::Cancelled { id }
+            | Call::Drop { id } => *id,
+        };
+        let mut target = target.lock();
+        let task = match id {
+            0 => &mut *target,
+            id => match tasks.get_mut(&id) {
+                Some(task) => task,
+                None => continue,
+            },
+        };
+        match call {
+            Call::AddChild { id, name, .. } => {
+                let child = task.add_child(name);
+                tasks.insert(id, child);
+            }
+            Call::Init { max, unit, .. } => task.init(max, unit),
+            Call::Set { step, .. } => task.set(step),
+            Call::IncBy { step, .. } => task.inc_by(step),
+            Call::SetName { name, .. } => task.set_name(name),
+            Call::Message { level, message, .. } => task.message(level, message),
+            Call::SetAttribute { key, value, .. } => task.set_attribute(key, value),
+            Call::Finished { .. } => task.finished(),
+            Call::Failed { .. } => task.failed(),
+            Call::Cancelled { .. } => task.cancelled(),
+            Call::Drop { id } => {
+                tasks.remove(&id);
+            }
+        }
+    }
+    Ok(())
+}
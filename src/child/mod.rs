@@ -0,0 +1,97 @@
+/*!
+Show the progress of child processes as part of the progress of their parent.
+
+The parent creates a [`Graft`](./struct.Graft.html) for the [`Progress`](../trait.Progress.html) to show the progress of a
+child process under, and [configures](./struct.Graft.html#method.configure) the command spawning it.
+This hands the child the address to report to in the [`ENV_VAR`](./constant.ENV_VAR.html) environment variable,
+which the child picks up with [`Reporter::from_env()`](./struct.Reporter.html#method.from_env).
+
+The parent listens on the loopback interface, and only accepts connections sending the random token that is part of
+the environment variable, so other local processes can't alter its progress.
+
+The `Reporter` implements `Progress` by forwarding every call to the parent, where it is applied to the grafted `Progress`.
+As `Graft` can graft under any `Progress`, including a `Reporter`, processes can be nested arbitrarily.
+
+```rust,no_run
+# fn main() -> std::io::Result<()> {
+use prodash::{child::{Graft, Reporter}, Progress};
+
+// in the parent process
+let tree = prodash::Tree::new();
+let graft = Graft::new(tree.add_child("helper"))?;
+let status = graft.configure(&mut std::process::Command::new("helper")).status()?;
+
+// in the child process
+if let Some(mut progress) = Reporter::from_env()? {
+    let mut task = progress.add_child("work");
+    task.init(Some(10), None);
+    task.inc();
+}
+# Ok(())
+# }
+```
+*/
+use crate::{messages::MessageLevel, progress::Step, Unit};
+use serde::{Deserialize, Serialize};
+
+mod graft;
+pub use graft::Graft;
+
+mod reporter;
+pub use reporter::Reporter;
+
+#[cfg(all(test, feature = "progress-tree"))]
+mod tests;
+
+/// The environment variable holding the address of the parent process to report progress to,
+/// formatted as `<token>@<address>` with the token authenticating the child.
+pub const ENV_VAR: &str = "PRODASH_PARENT";
+
+/// A call to a `Progress` method, made on the task with the given `id`, where `0` is the grafted task itself.
+#[derive(Serialize, Deserialize)]
+enum Call {
+    AddChild {
+        parent: u64,
+        id: u64,
+        name: String,
+    },
+    Init {
+        id: u64,
+        max: Option<Step>,
+        unit: Option<Unit>,
+    },
+    Set {
+        id: u64,
+        step: Step,
+    },
+    IncBy {
+        id: u64,
+        step: Step,
+    },
+    SetName {
+        id: u64,
+        name: String,
+    },
+    Message {
+        id: u64,
+        level: MessageLevel,
+        message: String,
+    },
+    SetAttribute {
+        id: u64,
+        key: String,
+        value: String,
+    },
+    Finished {
+        id: u64,
+    },
+    Failed {
+        id: u64,
+    },
+    Cancelled {
+        id: u64,
+    },
+    Drop {
+        id: u64,
+    },
+}
@@ -0,0 +1,220 @@
+use crate::{
+    child::{Call, ENV_VAR},
+    messages::MessageLevel,
+    progress::Step,
+    Progress, Unit,
+};
+use parking_lot::Mutex;
+use std::{
+    io::{self, BufWriter, Write},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    time::Duration,
+};
+
+/// How often buffered progress is sent to the parent.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A [`Progress`](../trait.Progress.html) implementation for child processes, forwarding all calls to the
+/// [`Graft`](./struct.Graft.html) of their parent process.
+///
+/// Progress is sent in batches, whereas everything else is sent right away.
+/// If the parent goes away, all calls are silently discarded.
+pub struct Reporter {
+    id: u64,
+    name: String,
+    step: Step,
+    max: Option<Step>,
+    unit: Option<Unit>,
+    connection: Arc<Connection>,
+}
+
+struct Connection {
+    next_id: AtomicU64,
+    out: Mutex<BufWriter<TcpStream>>,
+}
+
+impl Connection {
+    fn send(&self, call: &Call, flush: bool) {
+        let mut out = self.out.lock();
+        let res = serde_json::to_writer(&mut *out, call)
+            .map_err(io::Error::from)
+            .and_then(|_| out.write_all(b"\n"))
+            .and_then(|_| if flush { out.flush() } else { Ok(()) });
+        // There is nothing we can do if the parent is gone, and the child should be able to finish its work regardless.
+        res.ok();
+    }
+}
+
+/// Initialization
+impl Reporter {
+    /// Connect to the parent process named in the [`ENV_VAR`](./constant.ENV_VAR.html) environment variable,
+    /// or return `None` if it isn't set, as the process wasn't started by a parent wanting to see its progress.
+    ///
+    /// The returned instance represents the task the parent grafted the progress of this process under.
+    pub fn from_env() -> io::Result<Option<Reporter>> {
+        match std::env::var(ENV_VAR) {
+            Ok(parent) => Reporter::connect(parent.as_str()).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Connect to the `parent`, given as `<token>@<address>`.
+    pub(crate) fn connect(parent: &str) -> io::Result<Reporter> {
+        let (token, address) = parent.split_once('@').ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} must be formatted as <token>@<address>", ENV_VAR),
+            )
+        })?;
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        writeln!(stream, "{}", token)?;
+        let connection = Arc::new(Connection {
+            next_id: AtomicU64::new(1),
+            out: Mutex::new(BufWriter::new(stream)),
+        });
+        std::thread::spawn({
+            let connection = Arc::downgrade(&connection);
+            move || flush_periodically(connection)
+        });
+        Ok(Reporter {
+            id: 0,
+            name: String::new(),
+            step: 0,
+            max: None,
+            unit: None,
+            connection,
+        })
+    }
+}
+
+fn flush_periodically(connection: Weak<Connection>) {
+    loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        match connection.upgrade() {
+            Some(connection) => {
+                if connection.out.lock().flush().is_err() {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+}
+
+impl Progress for Reporter {
+    type SubProgress = Reporter;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        let name = name.into();
+        let id = self.connection.next_id.fetch_add(1, Ordering::Relaxed);
+        self.connection.send(
+            &Call::AddChild {
+                parent: self.id,
+                id,
+                name: name.clone(),
+            },
+            true,
+        );
+        Reporter {
+            id,
+            name,
+            step: 0,
+            max: None,
+            unit: None,
+            connection: self.connection.clone(),
+        }
+    }
+
+    fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
+        self.step = 0;
+        self.max = max;
+        self.unit = unit.clone();
+        self.connection.send(&Call::Init { id: self.id, max, unit }, true);
+    }
+
+    fn set(&mut self, step: Step) {
+        self.step = step;
+        self.connection.send(&Call::Set { id: self.id, step }, false);
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.unit.clone()
+    }
+
+    fn max(&self) -> Option<Step> {
+        self.max
+    }
+
+    fn step(&self) -> Step {
+        self.step
+    }
+
+    fn inc_by(&mut self, step: Step) {
+        self.step += step;
+        self.connection.send(&Call::IncBy { id: self.id, step }, false);
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+        self.connection.send(
+            &Call::SetName {
+                id: self.id,
+                name: self.name.clone(),
+            },
+            true,
+        );
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        self.connection.send(
+            &Call::Message {
+                id: self.id,
+                level,
+                message: message.into(),
+            },
+            true,
+        );
+    }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.connection.send(
+            &Call::SetAttribute {
+                id: self.id,
+                key: key.into(),
+                value: value.into(),
+            },
+            true,
+        );
+    }
+
+    fn finished(&mut self) {
+        self.connection.send(&Call::Finished { id: self.id }, true);
+    }
+
+    fn failed(&mut self) {
+        self.connection.send(&Call::Failed { id: self.id }, true);
+    }
+
+    fn cancelled(&mut self) {
+        self.connection.send(&Call::Cancelled { id: self.id }, true);
+    }
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            self.connection.send(&Call::Drop { id: self.id }, true);
+        } else {
+            self.connection.out.lock().flush().ok();
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    child::{Graft, Reporter, ENV_VAR},
+    progress::{Key, State, Task},
+    Progress, Tree,
+};
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(5), "condition not met in time");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Returns the token and address a child process configured by `graft` would report to.
+fn address(graft: &Graft) -> String {
+    let mut command = Command::new("child");
+    graft.configure(&mut command);
+    command
+        .get_envs()
+        .find(|(name, _)| *name == ENV_VAR)
+        .and_then(|(_, value)| value)
+        .and_then(|value| value.to_str())
+        .expect("environment variable to be set")
+        .to_owned()
+}
+
+fn connect(graft: &Graft) -> Reporter {
+    Reporter::connect(&address(graft)).unwrap()
+}
+
+/// Connect to `graft` directly, sending the given `token` first.
+fn connect_with_token(graft: &Graft, token: &str) -> std::net::TcpStream {
+    use std::io::Write;
+    let parent = address(graft);
+    let (_, address) = parent.split_once('@').expect("token and address");
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    writeln!(stream, "{}", token).unwrap();
+    stream
+}
+
+fn snapshot(tree: &Tree) -> Vec<(Key, Task)> {
+    let mut out = Vec::new();
+    tree.sorted_snapshot(&mut out);
+    out
+}
+
+#[test]
+fn tasks_and_messages_of_children_appear_under_the_grafted_item() {
+    let tree = Tree::new();
+    let graft = Graft::new(tree.add_child("helper")).unwrap();
+    let mut child = connect(&graft);
+
+    child.init(Some(2), None);
+    child.inc();
+    let mut task = child.add_child("work");
+    let mut nested = task.add_child("nested");
+    nested.init(Some(10), None);
+    nested.inc_by(7);
+    nested.info("almost there");
+    nested.finished();
+
+    wait_for(|| {
+        let tasks = snapshot(&tree);
+        tasks.len() == 3
+            && tasks[2].1.progress.as_ref().map(|p| (p.step, p.state.is_terminal())) == Some((7, true))
+            && tasks[0].1.progress.as_ref().map(|p| p.step) == Some(1)
+    });
+    let tasks = snapshot(&tree);
+    let names: Vec<_> = tasks.iter().map(|(_, task)| task.name.as_str()).collect();
+    assert_eq!(names, vec!["helper", "work", "nested"]);
+    assert_eq!(tasks[2].0.level(), 3, "nested tasks keep their place in the hierarchy");
+    assert!(matches!(
+        tasks[2].1.progress.as_ref().map(|p| p.state),
+        Some(State::Finished(_))
+    ));
+
+    let mut messages = Vec::new();
+    tree.copy_messages(&mut messages);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].path, vec!["helper", "work", "nested"]);
+
+    drop(nested);
+    wait_for(|| snapshot(&tree).len() == 2);
+    drop(task);
+    drop(child);
+    wait_for(|| snapshot(&tree).len() == 1);
+}
+
+#[test]
+fn tasks_of_disconnected_children_are_removed() {
+    use std::io::Write;
+
+    let tree = Tree::new();
+    let graft = Graft::new(tree.add_child("helper")).unwrap();
+    // a child which goes away without dropping its tasks, as if it crashed
+    let parent = address(&graft);
+    let (token, _) = parent.split_once('@').expect("token and address");
+    let mut stream = connect_with_token(&graft, token);
+    writeln!(stream, r#"{{"AddChild":{{"parent":0,"id":1,"name":"work"}}}}"#).unwrap();
+    wait_for(|| snapshot(&tree).len() == 2);
+
+    drop(stream);
+    wait_for(|| snapshot(&tree).len() == 1);
+}
+
+#[test]
+fn connections_without_the_token_are_ignored() {
+    use std::io::{Read, Write};
+
+    let tree = Tree::new();
+    let graft = Graft::new(tree.add_child("helper")).unwrap();
+    let mut stream = connect_with_token(&graft, "guessed");
+    writeln!(stream, r#"{{"AddChild":{{"parent":0,"id":1,"name":"intruder"}}}}"#).ok();
+    assert_eq!(
+        stream.read(&mut [0; 1]).ok(),
+        Some(0),
+        "the connection is closed by the parent"
+    );
+
+    let mut child = connect(&graft);
+    let _work = child.add_child("work");
+    wait_for(|| snapshot(&tree).len() == 2);
+    let tasks = snapshot(&tree);
+    assert_eq!(tasks[1].1.name, "work", "only children knowing the token can add tasks");
+}
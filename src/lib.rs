@@ -41,6 +41,8 @@ pub use tree::{Options as TreeOptions, Root as Tree};
 
 pub mod render;
 
+#[cfg(feature = "progress-tree-log")]
+pub use log::debug;
 #[cfg(feature = "progress-tree-log")]
 pub use log::info;
 #[cfg(feature = "progress-tree-log")]
@@ -56,14 +58,29 @@ pub use unit::Unit;
 pub mod messages;
 pub mod progress;
 
+#[cfg(feature = "remote")]
+pub mod remote;
+
+#[cfg(feature = "child-process")]
+pub mod child;
+
+#[cfg(feature = "record")]
+pub mod record;
+
 mod traits;
-pub use traits::{Progress, Root};
+pub use traits::{DynProgress, Progress, Root};
 
 mod throughput;
 pub use crate::throughput::Throughput;
 
 #[cfg(not(feature = "progress-tree-log"))]
 mod log {
+    /// Stub
+    #[macro_export(local_inner_macros)]
+    macro_rules! debug {
+        (target: $target:expr, $($arg:tt)+) => {};
+        ($($arg:tt)+) => {};
+    }
     /// Stub
     #[macro_export(local_inner_macros)]
     macro_rules! warn {
@@ -1,20 +1,27 @@
+use crate::progress::Key;
 use std::time::SystemTime;
 
-/// The severity of a message
+/// The severity of a message, ordered from least to most severe.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageLevel {
+    /// Detailed information for those debugging the task, usually too noisy to be shown
+    Debug,
     /// Rarely sent information related to the progress, not to be confused with the progress itself
     Info,
-    /// Used to indicate that a task has failed, along with the reason
-    Failure,
     /// Indicates a task was completed successfully
     Success,
+    /// Indicates a recoverable problem, like a retry or a fallback, that didn't stop the task
+    Warning,
+    /// Used to indicate that a task has failed, along with the reason
+    Failure,
 }
 
 /// A message to be stored along with the progress tree.
 ///
 /// It is created by [`Tree::message(…)`](./struct.Item.html#method.message).
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     /// The time at which the message was sent.
     pub time: SystemTime,
@@ -22,37 +29,141 @@ pub struct Message {
     pub level: MessageLevel,
     /// The name of the task that created the `Message`
     pub origin: String,
+    /// The key of the task that created the `Message`
+    pub key: Key,
+    /// The names of all tasks from the top of the hierarchy down to the one that created the `Message`,
+    /// as far as they are still present in the tree.
+    pub path: Vec<String>,
     /// The message itself
     pub message: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+impl Message {
+    /// Returns true if this message was created by the task with the given `key`,
+    /// or by any task underneath it if `include_subtree` is true.
+    pub fn is_from(&self, key: &Key, include_subtree: bool) -> bool {
+        self.key == *key || (include_subtree && self.key.is_descendant_of(key))
+    }
+}
+
+/// A receiver of every message pushed into a [`MessageRingBuffer`], independently of its capacity.
+#[derive(Debug)]
+enum Subscriber {
+    Sync(std::sync::mpsc::Sender<Message>),
+    #[cfg(feature = "progress-tree-message-stream")]
+    Stream(async_channel::Sender<Message>),
+}
+
+impl Subscriber {
+    /// Send `message`, returning false if the receiving end is gone.
+    fn send(&self, message: Message) -> bool {
+        match self {
+            Subscriber::Sync(tx) => tx.send(message).is_ok(),
+            #[cfg(feature = "progress-tree-message-stream")]
+            Subscriber::Stream(tx) => tx.try_send(message).is_ok(),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct MessageRingBuffer {
     pub(crate) buf: Vec<Message>,
     cursor: usize,
     total: usize,
+    /// All subscribers along with the id they can be unsubscribed with.
+    subscribers: Vec<(u64, Subscriber)>,
+    next_subscriber_id: u64,
+}
+
+/// Subscribers are not cloned, as they subscribed to the original buffer only.
+impl Clone for MessageRingBuffer {
+    fn clone(&self) -> Self {
+        MessageRingBuffer {
+            buf: self.buf.clone(),
+            cursor: self.cursor,
+            total: self.total,
+            subscribers: Vec::new(),
+            next_subscriber_id: 0,
+        }
+    }
 }
 
+impl PartialEq for MessageRingBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.buf == other.buf && self.cursor == other.cursor && self.total == other.total
+    }
+}
+
+impl Eq for MessageRingBuffer {}
+
 impl MessageRingBuffer {
     pub fn with_capacity(capacity: usize) -> MessageRingBuffer {
         MessageRingBuffer {
             buf: Vec::with_capacity(capacity),
             cursor: 0,
             total: 0,
+            subscribers: Vec::new(),
+            next_subscriber_id: 0,
         }
     }
 
+    /// Return a receiver for all messages pushed from now on, each of which it receives exactly once,
+    /// no matter how many messages this buffer can hold.
+    ///
+    /// Messages are queued until they are received, and no more messages are sent once the receiver is dropped.
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<Message> {
+        self.subscribe_with_id().1
+    }
+
+    /// Like [`subscribe()`](#method.subscribe), but also return an id to [`unsubscribe(…)`](#method.unsubscribe) with.
+    pub(crate) fn subscribe_with_id(&mut self) -> (u64, std::sync::mpsc::Receiver<Message>) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        (self.add_subscriber(Subscriber::Sync(tx)), rx)
+    }
+
+    /// Stop sending messages to the subscriber with the given `id`, which disconnects it once it received all prior messages.
+    #[cfg(feature = "progress-tree")]
+    pub(crate) fn unsubscribe(&mut self, id: u64) {
+        self.subscribers.retain(|(subscriber_id, _)| *subscriber_id != id);
+    }
+
+    fn add_subscriber(&mut self, subscriber: Subscriber) -> u64 {
+        let id = self.next_subscriber_id;
+        self.next_subscriber_id += 1;
+        self.subscribers.push((id, subscriber));
+        id
+    }
+
+    /// Like [`subscribe()`](#method.subscribe), but return a `Stream` of messages instead.
+    #[cfg(feature = "progress-tree-message-stream")]
+    pub fn subscribe_stream(&mut self) -> impl futures_core::Stream<Item = Message> {
+        let (tx, rx) = async_channel::unbounded();
+        self.add_subscriber(Subscriber::Stream(tx));
+        rx
+    }
+
     fn has_capacity(&self) -> bool {
         self.buf.len() < self.buf.capacity()
     }
 
-    pub fn push_overwrite(&mut self, level: MessageLevel, origin: String, message: impl Into<String>) {
-        let msg = Message {
+    /// Store a `message` of the given `level`, sent by the task with the given `key` and `path` of names,
+    /// overwriting the oldest message if there is no capacity left.
+    pub fn push_overwrite(&mut self, level: MessageLevel, key: Key, path: Vec<String>, message: impl Into<String>) {
+        self.push(Message {
             time: SystemTime::now(),
             level,
-            origin,
+            origin: path.last().cloned().unwrap_or_default(),
+            key,
+            path,
             message: message.into(),
-        };
+        })
+    }
+
+    /// Store the given `msg` as is, overwriting the oldest message if there is no capacity left.
+    pub fn push(&mut self, msg: Message) {
+        if !self.subscribers.is_empty() {
+            self.subscribers.retain(|(_, s)| s.send(msg.clone()));
+        }
         if self.has_capacity() {
             self.buf.push(msg)
         } else {
@@ -73,6 +184,13 @@ impl MessageRingBuffer {
         }
     }
 
+    /// Copy all messages sent by the task with the given `key`, or by any task underneath it if `include_subtree` is true,
+    /// into `out`, from oldest to newest.
+    pub fn copy_from(&self, key: &Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.copy_all(out);
+        out.retain(|m| m.is_from(key, include_subtree));
+    }
+
     pub fn copy_new(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
         out.clear();
         match prev {
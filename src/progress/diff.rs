@@ -0,0 +1,139 @@
+use crate::progress::{Key, Task, Value};
+use std::cmp::Ordering;
+
+/// The way a task changed between two snapshots, as produced by [`SnapshotDiff`](./struct.SnapshotDiff.html).
+#[derive(Clone, Copy, Debug)]
+pub enum Change<'a> {
+    /// The task is new, and only present in the current snapshot.
+    Added(&'a Task),
+    /// The task is gone, and only present in the previous snapshot.
+    Removed(&'a Task),
+    /// The name of the task changed.
+    Renamed {
+        /// The name in the previous snapshot.
+        from: &'a str,
+        /// The name in the current snapshot.
+        to: &'a str,
+    },
+    /// The progress of the task changed, including its state, or it was initialised.
+    Progressed {
+        /// The progress in the previous snapshot.
+        from: Option<&'a Value>,
+        /// The progress in the current snapshot.
+        to: Option<&'a Value>,
+    },
+    /// Anything else about the task changed, like its attributes or whether it was dropped.
+    Altered {
+        /// The task in the previous snapshot.
+        from: &'a Task,
+        /// The task in the current snapshot.
+        to: &'a Task,
+    },
+}
+
+/// An iterator over all changes between two snapshots as obtained by [`Root::sorted_snapshot(…)`](../trait.Root.html#tymethod.sorted_snapshot),
+/// yielding the key of each task that changed along with the way it changed, in order of keys.
+///
+/// A task changing in multiple ways yields one change for each of them, with `Renamed` before `Progressed` before `Altered`.
+///
+/// ```rust
+/// use prodash::progress::{Change, SnapshotDiff};
+/// # let (previous, current) = (Vec::new(), Vec::new());
+/// for (key, change) in SnapshotDiff::new(&previous, &current) {
+///     if let Change::Added(task) = change {
+///         println!("{:?} was added as '{}'", key, task.name);
+///     }
+/// }
+/// ```
+pub struct SnapshotDiff<'a> {
+    previous: &'a [(Key, Task)],
+    current: &'a [(Key, Task)],
+    pending: Vec<(&'a Key, Change<'a>)>,
+}
+
+impl<'a> SnapshotDiff<'a> {
+    /// Compare the `previous` snapshot to the `current` one, both of which must be sorted by key.
+    pub fn new(previous: &'a [(Key, Task)], current: &'a [(Key, Task)]) -> Self {
+        SnapshotDiff {
+            previous,
+            current,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for SnapshotDiff<'a> {
+    type Item = (&'a Key, Change<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(change) = self.pending.pop() {
+                return Some(change);
+            }
+            let (previous, current) = match (self.previous.split_first(), self.current.split_first()) {
+                (None, None) => return None,
+                (Some(((key, task), rest)), None) => {
+                    self.previous = rest;
+                    return Some((key, Change::Removed(task)));
+                }
+                (None, Some(((key, task), rest))) => {
+                    self.current = rest;
+                    return Some((key, Change::Added(task)));
+                }
+                (Some((previous, previous_rest)), Some((current, current_rest))) => match previous.0.cmp(&current.0) {
+                    Ordering::Less => {
+                        self.previous = previous_rest;
+                        return Some((&previous.0, Change::Removed(&previous.1)));
+                    }
+                    Ordering::Greater => {
+                        self.current = current_rest;
+                        return Some((&current.0, Change::Added(&current.1)));
+                    }
+                    Ordering::Equal => {
+                        self.previous = previous_rest;
+                        self.current = current_rest;
+                        (previous, current)
+                    }
+                },
+            };
+
+            let (key, from, to) = (&current.0, &previous.1, &current.1);
+            if from.dropped_at != to.dropped_at || from.attributes != to.attributes {
+                self.pending.push((key, Change::Altered { from, to }));
+            }
+            if !same_progress(from.progress.as_ref(), to.progress.as_ref()) {
+                self.pending.push((
+                    key,
+                    Change::Progressed {
+                        from: from.progress.as_ref(),
+                        to: to.progress.as_ref(),
+                    },
+                ));
+            }
+            if from.name != to.name {
+                self.pending.push((
+                    key,
+                    Change::Renamed {
+                        from: &from.name,
+                        to: &to.name,
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// Units aren't compared, as they can only change along with the time of initialisation.
+fn same_progress(lhs: Option<&Value>, rhs: Option<&Value>) -> bool {
+    match (lhs, rhs) {
+        (None, None) => true,
+        (Some(lhs), Some(rhs)) => {
+            lhs.step == rhs.step
+                && lhs.done_at == rhs.done_at
+                && lhs.state == rhs.state
+                && lhs.initialized_at == rhs.initialized_at
+                && lhs.last_advanced_at == rhs.last_advanced_at
+        }
+        _ => false,
+    }
+}
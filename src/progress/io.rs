@@ -0,0 +1,158 @@
+use crate::{progress::Step, unit, Progress, Unit};
+use std::io;
+
+/// The unit used when initializing progress with a known amount of bytes.
+///
+/// It displays bytes dynamically with the `unit-bytes` feature, and is a plain label otherwise.
+fn bytes() -> Unit {
+    #[cfg(feature = "unit-bytes")]
+    {
+        unit::dynamic_and_mode(unit::Bytes, unit::display::Mode::with_throughput())
+    }
+    #[cfg(not(feature = "unit-bytes"))]
+    {
+        unit::label_and_mode("B", unit::display::Mode::with_throughput())
+    }
+}
+
+/// A reader which increments its `progress` by the amount of bytes read from the `inner` reader.
+///
+/// For readers implementing `BufRead`, bytes are counted once they are consumed.
+/// With the `progress-io-async` feature, it also implements `AsyncRead` and `AsyncBufRead` if `inner` does.
+pub struct Read<R, P> {
+    /// The reader to read bytes from
+    pub inner: R,
+    /// The progress to increment with each byte read
+    pub progress: P,
+}
+
+impl<R, P: Progress> Read<R, P> {
+    /// Wrap `inner` to increment `progress` with each byte read from it, without initializing `progress`.
+    pub fn new(inner: R, progress: P) -> Self {
+        Read { inner, progress }
+    }
+
+    /// Wrap `inner` like [`new(…)`](#method.new), but initialize `progress` with a byte unit, and `len` as maximum if known.
+    pub fn with_len(inner: R, mut progress: P, len: Option<Step>) -> Self {
+        progress.init(len, Some(bytes()));
+        Read { inner, progress }
+    }
+
+    /// Return the inner reader and the progress.
+    pub fn into_parts(self) -> (R, P) {
+        (self.inner, self.progress)
+    }
+}
+
+impl<R: io::Read, P: Progress> io::Read for Read<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.progress.inc_by(bytes_read);
+        Ok(bytes_read)
+    }
+}
+
+impl<R: io::BufRead, P: Progress> io::BufRead for Read<R, P> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.progress.inc_by(amt)
+    }
+}
+
+/// A writer which increments its `progress` by the amount of bytes written to the `inner` writer.
+///
+/// With the `progress-io-async` feature, it also implements `AsyncWrite` if `inner` does.
+pub struct Write<W, P> {
+    /// The writer to write bytes to
+    pub inner: W,
+    /// The progress to increment with each byte written
+    pub progress: P,
+}
+
+impl<W, P: Progress> Write<W, P> {
+    /// Wrap `inner` to increment `progress` with each byte written to it, without initializing `progress`.
+    pub fn new(inner: W, progress: P) -> Self {
+        Write { inner, progress }
+    }
+
+    /// Wrap `inner` like [`new(…)`](#method.new), but initialize `progress` with a byte unit, and `len` as maximum if known.
+    pub fn with_len(inner: W, mut progress: P, len: Option<Step>) -> Self {
+        progress.init(len, Some(bytes()));
+        Write { inner, progress }
+    }
+
+    /// Return the inner writer and the progress.
+    pub fn into_parts(self) -> (W, P) {
+        (self.inner, self.progress)
+    }
+}
+
+impl<W: io::Write, P: Progress> io::Write for Write<W, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.progress.inc_by(bytes_written);
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "progress-io-async")]
+mod async_io {
+    use super::{Read, Write};
+    use crate::Progress;
+    use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    impl<R: AsyncRead + Unpin, P: Progress + Unpin> AsyncRead for Read<R, P> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+            if let Poll::Ready(Ok(bytes_read)) = res {
+                this.progress.inc_by(bytes_read);
+            }
+            res
+        }
+    }
+
+    impl<R: AsyncBufRead + Unpin, P: Progress + Unpin> AsyncBufRead for Read<R, P> {
+        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+            Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+        }
+
+        fn consume(self: Pin<&mut Self>, amt: usize) {
+            let this = self.get_mut();
+            Pin::new(&mut this.inner).consume(amt);
+            this.progress.inc_by(amt)
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin, P: Progress + Unpin> AsyncWrite for Write<W, P> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+            if let Poll::Ready(Ok(bytes_written)) = res {
+                this.progress.inc_by(bytes_written);
+            }
+            res
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_close(cx)
+        }
+    }
+}
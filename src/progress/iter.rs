@@ -0,0 +1,99 @@
+use crate::Progress;
+use std::time::Instant;
+
+/// Extends all iterators with [`with_progress(…)`](#method.with_progress).
+pub trait IteratorExt: Iterator + Sized {
+    /// Increment `progress` for each item yielded by this iterator, and show its throughput once it is exhausted.
+    ///
+    /// `progress` is initialized right away, with the upper bound of the `size_hint()` as maximum if there is one.
+    fn with_progress<P: Progress>(self, mut progress: P) -> WithProgress<Self, P> {
+        progress.init(self.size_hint().1, None);
+        WithProgress::new(self, progress)
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// Extends all streams with [`with_progress(…)`](#method.with_progress).
+#[cfg(feature = "progress-stream")]
+pub trait StreamExt: futures_core::Stream + Sized {
+    /// Increment `progress` for each item yielded by this stream, and show its throughput once it is exhausted.
+    ///
+    /// `progress` is initialized right away, with the upper bound of the `size_hint()` as maximum if there is one.
+    fn with_progress<P: Progress>(self, mut progress: P) -> WithProgress<Self, P> {
+        progress.init(self.size_hint().1, None);
+        WithProgress::new(self, progress)
+    }
+}
+
+#[cfg(feature = "progress-stream")]
+impl<S: futures_core::Stream> StreamExt for S {}
+
+/// An iterator or stream incrementing its `progress` with each item yielded by `inner`.
+///
+/// It is created by [`IteratorExt::with_progress(…)`](./trait.IteratorExt.html#method.with_progress)
+/// and [`StreamExt::with_progress(…)`](./trait.StreamExt.html#method.with_progress).
+pub struct WithProgress<I, P> {
+    /// The iterator or stream yielding items
+    pub inner: I,
+    /// The progress to increment with each item
+    pub progress: P,
+    start: Instant,
+    exhausted: bool,
+}
+
+impl<I, P: Progress> WithProgress<I, P> {
+    fn new(inner: I, progress: P) -> Self {
+        WithProgress {
+            inner,
+            progress,
+            start: Instant::now(),
+            exhausted: false,
+        }
+    }
+
+    /// Keep track of the `item` yielded by `inner`, showing the throughput the first time there is none.
+    fn record<T>(&mut self, item: Option<T>) -> Option<T> {
+        match item {
+            Some(_) => self.progress.inc(),
+            None if !self.exhausted => {
+                self.exhausted = true;
+                self.progress.show_throughput(self.start);
+            }
+            None => {}
+        }
+        item
+    }
+}
+
+impl<I: Iterator, P: Progress> Iterator for WithProgress<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next();
+        self.record(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "progress-stream")]
+impl<S: futures_core::Stream + Unpin, P: Progress + Unpin> futures_core::Stream for WithProgress<S, P> {
+    type Item = S::Item;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        std::pin::Pin::new(&mut this.inner)
+            .poll_next(cx)
+            .map(|item| this.record(item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
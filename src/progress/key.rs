@@ -1,14 +1,18 @@
 use crate::progress::Task;
 use std::ops::{Index, IndexMut};
 
-// NOTE: This means we will show weird behaviour if there are more than 2^16 tasks at the same time on a level
-pub type Level = u8; // a level in the hierarchy of key components
+pub type Level = usize; // a level in the hierarchy of key components
 
-pub(crate) type Id = u16;
+/// The identifier of a task among its siblings. Only one of the siblings alive at the same time may use it.
+pub(crate) type Id = u32;
 
 /// A type identifying a spot in the hierarchy of `Tree` items.
-#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Key(Option<Id>, Option<Id>, Option<Id>, Option<Id>);
+///
+/// Each level of hierarchy adds one path component, and there is no limit to the depth of the hierarchy.
+/// Keys sort in hierarchy order, i.e. parents sort before their children, which sort before the parent's next sibling.
+#[derive(Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key(Vec<Id>);
 
 /// Determines if a sibling is above or below in the given level of hierarchy
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -42,94 +46,76 @@ impl Default for SiblingLocation {
 }
 
 /// A type providing information about what's above and below `Tree` items.
-#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Adjacency(
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-);
+///
+/// It holds one `SiblingLocation` per level of hierarchy, the first one belonging to level 1, up to the
+/// highest level with a sibling. Levels past the end are considered `SiblingLocation::NotFound`.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Adjacency(pub Vec<SiblingLocation>);
 
 impl Adjacency {
+    /// The highest level at which a sibling was found, or 0 if there is none.
     pub fn level(&self) -> Level {
-        use SiblingLocation::*;
-        match self {
-            Adjacency(NotFound, NotFound, NotFound, NotFound) => 0,
-            Adjacency(_a, NotFound, NotFound, NotFound) => 1,
-            Adjacency(_a, _b, NotFound, NotFound) => 2,
-            Adjacency(_a, _b, _c, NotFound) => 3,
-            Adjacency(_a, _b, _c, _d) => 4,
-        }
+        self.0
+            .iter()
+            .rposition(|location| *location != SiblingLocation::NotFound)
+            .map(|index| index + 1)
+            .unwrap_or(0)
     }
     pub fn get(&self, level: Level) -> Option<&SiblingLocation> {
-        Some(match level {
-            1 => &self.0,
-            2 => &self.1,
-            3 => &self.2,
-            4 => &self.3,
-            _ => return None,
-        })
+        level.checked_sub(1).and_then(|index| self.0.get(index))
     }
     pub fn get_mut(&mut self, level: Level) -> Option<&mut SiblingLocation> {
-        Some(match level {
-            1 => &mut self.0,
-            2 => &mut self.1,
-            3 => &mut self.2,
-            4 => &mut self.3,
-            _ => return None,
-        })
+        level.checked_sub(1).and_then(move |index| self.0.get_mut(index))
     }
 }
 
 impl Index<Level> for Adjacency {
     type Output = SiblingLocation;
     fn index(&self, index: Level) -> &Self::Output {
-        self.get(index).expect("adjacency index in bound")
+        self.get(index).unwrap_or(&SiblingLocation::NotFound)
     }
 }
 
 impl IndexMut<Level> for Adjacency {
     fn index_mut(&mut self, index: Level) -> &mut Self::Output {
+        if self.0.len() < index {
+            self.0.resize(index, SiblingLocation::NotFound);
+        }
         self.get_mut(index).expect("adjacency index in bound")
     }
 }
 
 impl Key {
-    pub fn add_child(self, child_id: Id) -> Key {
-        match self {
-            Key(None, None, None, None) => Key(Some(child_id), None, None, None),
-            Key(a, None, None, None) => Key(a, Some(child_id), None, None),
-            Key(a, b, None, None) => Key(a, b, Some(child_id), None),
-            Key(a, b, c, None) => Key(a, b, c, Some(child_id)),
-            Key(a, b, c, _d) => {
-                crate::warn!("Maximum nesting level reached. Adding tasks to current parent");
-                Key(a, b, c, Some(child_id))
-            }
-        }
+    /// Return a new key for a child of this key with the given `child_id`, one level below this one.
+    pub fn add_child(&self, child_id: Id) -> Key {
+        let mut ids = Vec::with_capacity(self.0.len() + 1);
+        ids.extend_from_slice(&self.0);
+        ids.push(child_id);
+        Key(ids)
     }
 
     /// The level of hierarchy a node is placed in, i.e. the amount of path components
     pub fn level(&self) -> Level {
-        match self {
-            Key(None, None, None, None) => 0,
-            Key(Some(_), None, None, None) => 1,
-            Key(Some(_), Some(_), None, None) => 2,
-            Key(Some(_), Some(_), Some(_), None) => 3,
-            Key(Some(_), Some(_), Some(_), Some(_)) => 4,
-            _ => unreachable!("This is a bug - Keys follow a certain pattern"),
-        }
+        self.0.len()
     }
 
-    fn get(&self, level: Level) -> Option<&Id> {
-        match level {
-            1 => self.0.as_ref(),
-            2 => self.1.as_ref(),
-            3 => self.2.as_ref(),
-            4 => self.3.as_ref(),
+    /// Returns the key of this key's parent, or `None` if it is at the top of the hierarchy.
+    pub fn parent(&self) -> Option<Key> {
+        match self.0.split_last() {
+            Some((_, parent)) if !parent.is_empty() => Some(Key(parent.to_vec())),
             _ => None,
         }
     }
 
+    /// Returns true if this key is placed anywhere below `parent` in the hierarchy.
+    pub fn is_descendant_of(&self, parent: &Key) -> bool {
+        self.0.len() > parent.0.len() && self.0.starts_with(&parent.0)
+    }
+
+    fn get(&self, level: Level) -> Option<&Id> {
+        level.checked_sub(1).and_then(|index| self.0.get(index))
+    }
+
     pub fn shares_parent_with(&self, other: &Key, parent_level: Level) -> bool {
         if parent_level < 1 {
             return true;
@@ -164,7 +150,6 @@ impl Key {
             key: &Key,
             key_level: Level,
             current_level: Level,
-            _id_at_level: Id,
         ) -> Option<usize> {
             iter.map(|(k, _)| k)
                 .take_while(|other| key.shares_parent_with(other, current_level.saturating_sub(1)))
@@ -179,13 +164,12 @@ impl Key {
                 .map(|(idx, _)| idx)
         };
 
-        let upward_iter = |from: usize, key: &Key, level: Level, id_at_level: Id| {
-            search(sorted[..from].iter().rev(), key, key_level, level, id_at_level)
-        };
-        let downward_iter = |from: usize, key: &Key, level: Level, id_at_level: Id| {
+        let upward_iter =
+            |from: usize, key: &Key, level: Level| search(sorted[..from].iter().rev(), key, key_level, level);
+        let downward_iter = |from: usize, key: &Key, level: Level| {
             sorted
                 .get(from + 1..)
-                .and_then(|s| search(s.iter(), key, key_level, level, id_at_level))
+                .and_then(|s| search(s.iter(), key, key_level, level))
         };
 
         {
@@ -195,7 +179,7 @@ impl Key {
                     adjecency[level].merge(Above); // the root or any other sibling on level one
                     continue;
                 }
-                if let Some(key_offset) = upward_iter(cursor, &key, level, key[level]) {
+                if let Some(key_offset) = upward_iter(cursor, key, level) {
                     cursor = index.saturating_sub(key_offset);
                     adjecency[level].merge(Above);
                 }
@@ -204,7 +188,7 @@ impl Key {
         {
             let mut cursor = index;
             for level in (1..=key_level).rev() {
-                if let Some(key_offset) = downward_iter(cursor, &key, level, key[level]) {
+                if let Some(key_offset) = downward_iter(cursor, key, level) {
                     cursor = index + key_offset;
                     adjecency[level].merge(Below);
                 }
@@ -219,13 +203,11 @@ impl Key {
                 AboveAndBelow => AboveAndBelow,
             };
         }
+        while adjecency.0.last() == Some(&NotFound) {
+            adjecency.0.pop();
+        }
         adjecency
     }
-
-    /// The maximum amount of path components we can represent.
-    pub const fn max_level() -> Level {
-        4
-    }
 }
 
 impl Index<Level> for Key {
@@ -102,10 +102,19 @@ impl Progress for Log {
         self.name.split(SEP).nth(1).map(ToOwned::to_owned)
     }
 
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        if self.current_level > self.max_level {
+            return;
+        }
+        log::info!("{} → {} = {}", self.name, key.into(), value.into());
+    }
+
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         let message: String = message.into();
         match level {
+            MessageLevel::Debug => log::debug!("·{} → {}", self.name, message),
             MessageLevel::Info => log::info!("ℹ{} → {}", self.name, message),
+            MessageLevel::Warning => log::warn!("⚠{} → {}", self.name, message),
             MessageLevel::Failure => log::error!("𐄂{} → {}", self.name, message),
             MessageLevel::Success => log::info!("✓{} → {}", self.name, message),
         }
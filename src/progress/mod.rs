@@ -1,9 +1,26 @@
-use crate::unit::Unit;
-use std::time::SystemTime;
+use crate::unit::{self, Unit};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
 
 pub mod key;
 mod utils;
 
+mod diff;
+pub use diff::{Change, SnapshotDiff};
+
+mod io;
+pub use io::{Read, Write};
+
+mod iter;
+#[cfg(feature = "progress-stream")]
+pub use iter::StreamExt;
+pub use iter::{IteratorExt, WithProgress};
+
+#[cfg(feature = "serde")]
+mod serialize;
+
 #[cfg(feature = "progress-log")]
 mod log;
 #[cfg(feature = "progress-log")]
@@ -17,8 +34,9 @@ pub use utils::{Discard, DoOrDiscard, Either, ThroughputOnDrop};
 /// The amount of steps a progress can make
 pub type Step = usize;
 
-/// Indicate whether a progress can or cannot be made.
+/// Indicate whether a progress can or cannot be made, or if the task is done.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum State {
     /// Indicates a task is blocked and cannot indicate progress, optionally until the
     /// given time. The task cannot easily be interrupted.
@@ -28,6 +46,27 @@ pub enum State {
     Halted(&'static str, Option<SystemTime>),
     /// The task is running
     Running,
+    /// The task finished successfully at the given time.
+    Finished(SystemTime),
+    /// The task failed at the given time.
+    Failed(SystemTime),
+    /// The task was cancelled at the given time, before it could finish.
+    Cancelled(SystemTime),
+}
+
+impl State {
+    /// Returns true if the task is done, whether it finished successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, State::Finished(_) | State::Failed(_) | State::Cancelled(_))
+    }
+
+    /// Returns the time at which the task was done, if it is in a terminal state.
+    pub fn ended_at(&self) -> Option<SystemTime> {
+        match self {
+            State::Finished(time) | State::Failed(time) | State::Cancelled(time) => Some(*time),
+            State::Blocked(_, _) | State::Halted(_, _) | State::Running => None,
+        }
+    }
 }
 
 impl Default for State {
@@ -38,6 +77,7 @@ impl Default for State {
 
 /// Progress associated with some item in the progress tree.
 #[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Value {
     /// The amount of progress currently made
     pub step: Step,
@@ -49,6 +89,10 @@ pub struct Value {
     pub unit: Option<Unit>,
     /// Whether progress can be made or not
     pub state: State,
+    /// The time at which the progress was initialised, if known.
+    pub initialized_at: Option<SystemTime>,
+    /// The time at which progress was last made, or `None` if there was none yet.
+    pub last_advanced_at: Option<SystemTime>,
 }
 
 impl Value {
@@ -58,13 +102,43 @@ impl Value {
     pub fn fraction(&self) -> Option<f32> {
         self.done_at.map(|done_at| self.step as f32 / done_at as f32)
     }
+
+    /// Returns the time that passed since the progress was initialised, or `None` if that time is unknown.
+    ///
+    /// For tasks in a terminal state, it's the time it took until they were done.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.initialized_at.and_then(|start| {
+            self.state
+                .ended_at()
+                .unwrap_or_else(SystemTime::now)
+                .duration_since(start)
+                .ok()
+        })
+    }
+
+    /// Estimate the time remaining until `done_at` is reached at the given `throughput`.
+    ///
+    /// Returns `None` unless the task is running, bounded and made progress within the throughput's timespan.
+    pub fn eta(&self, throughput: Option<unit::display::Throughput>) -> Option<Duration> {
+        match self.state {
+            State::Running => throughput
+                .zip(self.done_at)
+                .and_then(|(throughput, done_at)| throughput.eta(self.step, done_at)),
+            _ => None,
+        }
+    }
 }
 
 /// The value associated with a spot in the hierarchy.
 #[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Task {
     /// The name of the `Item` or task.
     pub name: String,
     /// The progress itself, unless this value belongs to an `Item` serving as organizational unit.
     pub progress: Option<Value>,
+    /// The time at which the `Item` was dropped, if it is still shown to linger with its final state.
+    pub dropped_at: Option<SystemTime>,
+    /// Structured information about the task, like the file it's working on, sorted by key.
+    pub attributes: BTreeMap<String, String>,
 }
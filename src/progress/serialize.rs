@@ -0,0 +1,37 @@
+use crate::unit::serialize::intern;
+use serde::{Deserialize, Deserializer};
+use std::time::SystemTime;
+
+/// A `progress::State` with owned reasons, which can be deserialized from any input.
+#[derive(Deserialize)]
+#[serde(rename = "State")]
+enum State {
+    Blocked(String, Option<SystemTime>),
+    Halted(String, Option<SystemTime>),
+    Running,
+    Finished(SystemTime),
+    Failed(SystemTime),
+    Cancelled(SystemTime),
+}
+
+impl From<State> for crate::progress::State {
+    fn from(state: State) -> Self {
+        use crate::progress::State::*;
+        match state {
+            State::Blocked(reason, eta) => Blocked(intern(reason), eta),
+            State::Halted(reason, eta) => Halted(intern(reason), eta),
+            State::Running => Running,
+            State::Finished(time) => Finished(time),
+            State::Failed(time) => Failed(time),
+            State::Cancelled(time) => Cancelled(time),
+        }
+    }
+}
+
+/// Reasons for blocked or halted tasks are leaked to obtain the static lifetime they need,
+/// but only once for each distinct reason, and only up to a limited amount of reasons.
+impl<'de> Deserialize<'de> for crate::progress::State {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        State::deserialize(deserializer).map(Into::into)
+    }
+}
@@ -109,6 +109,41 @@ where
             Either::Right(r) => r.message(level, message),
         }
     }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        match self {
+            Either::Left(l) => l.set_attribute(key, value),
+            Either::Right(r) => r.set_attribute(key, value),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        match self {
+            Either::Left(l) => l.is_cancelled(),
+            Either::Right(r) => r.is_cancelled(),
+        }
+    }
+
+    fn finished(&mut self) {
+        match self {
+            Either::Left(l) => l.finished(),
+            Either::Right(r) => r.finished(),
+        }
+    }
+
+    fn failed(&mut self) {
+        match self {
+            Either::Left(l) => l.failed(),
+            Either::Right(r) => r.failed(),
+        }
+    }
+
+    fn cancelled(&mut self) {
+        match self {
+            Either::Left(l) => l.cancelled(),
+            Either::Right(r) => r.cancelled(),
+        }
+    }
 }
 
 pub struct DoOrDiscard<T>(Either<T, Discard>);
@@ -187,6 +222,26 @@ where
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         self.0.message(level, message)
     }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.set_attribute(key, value)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    fn finished(&mut self) {
+        self.0.finished()
+    }
+
+    fn failed(&mut self) {
+        self.0.failed()
+    }
+
+    fn cancelled(&mut self) {
+        self.0.cancelled()
+    }
 }
 
 use std::time::Instant;
@@ -241,6 +296,26 @@ impl<T: Progress> Progress for ThroughputOnDrop<T> {
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         self.0.message(level, message)
     }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.set_attribute(key, value)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    fn finished(&mut self) {
+        self.0.finished()
+    }
+
+    fn failed(&mut self) {
+        self.0.failed()
+    }
+
+    fn cancelled(&mut self) {
+        self.0.cancelled()
+    }
 }
 
 impl<T: Progress> Drop for ThroughputOnDrop<T> {
@@ -0,0 +1,57 @@
+/*!
+Record how a [`Root`](../trait.Root.html) changes over time into a file, and replay it later with any renderer.
+
+```rust,no_run
+# fn main() -> std::io::Result<()> {
+use prodash::record::{Recorder, Replay};
+use std::time::Duration;
+
+let tree = prodash::Tree::new();
+let recorder = Recorder::to_file(tree.clone(), "session.ndjson", Duration::from_millis(100))?;
+// … do the work …
+recorder.stop()?;
+
+// later, possibly in another process, at twice the speed, and handed to any renderer
+let replay = Replay::from_file("session.ndjson")?.with_speed(2.0);
+# Ok(())
+# }
+```
+
+## Format
+
+Recordings consist of JSON objects, one per line. The first line is a header with the format `version`, the time
+the recording `started_at` and the `messages_capacity` of the recorded `Root`. Each following line is a frame with the time
+`elapsed` since the start, all `tasks` unless they didn't change since the previous frame, and all `messages` sent since then.
+*/
+use crate::{
+    messages::Message,
+    progress::{Key, Task},
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+mod recorder;
+pub use recorder::Recorder;
+
+mod replay;
+pub use replay::Replay;
+
+#[cfg(all(test, feature = "progress-tree"))]
+mod tests;
+
+/// The version of the recording format, which must match when replaying.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    started_at: SystemTime,
+    messages_capacity: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    elapsed: Duration,
+    tasks: Option<Vec<(Key, Task)>>,
+    messages: Vec<Message>,
+}
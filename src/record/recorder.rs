@@ -0,0 +1,123 @@
+use crate::{
+    record::{Frame, Header, FORMAT_VERSION},
+    Root,
+};
+use std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::mpsc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Records a [`Root`](../trait.Root.html) from a background thread, until it is stopped or dropped.
+pub struct Recorder {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<io::Result<()>>>,
+}
+
+/// Initialization
+impl Recorder {
+    /// Record `root` into a newly created file at `path`, looking for changes every `interval`.
+    pub fn to_file(
+        root: impl Root + Send + 'static,
+        path: impl AsRef<Path>,
+        interval: Duration,
+    ) -> io::Result<Recorder> {
+        let file = fs::File::create(path)?;
+        Ok(Recorder::to_writer(root, BufWriter::new(file), interval))
+    }
+
+    /// Record `root` into `out`, looking for changes every `interval`.
+    pub fn to_writer(
+        root: impl Root + Send + 'static,
+        out: impl Write + Send + 'static,
+        interval: Duration,
+    ) -> Recorder {
+        let (stop, stop_requested) = mpsc::channel();
+        let thread = std::thread::spawn(move || record(&root, out, interval, stop_requested));
+        Recorder {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Recorder {
+    /// Record the last changes and stop, returning the first error that occurred while writing, if any.
+    pub fn stop(mut self) -> io::Result<()> {
+        self.stop_and_join()
+    }
+
+    fn stop_and_join(&mut self) -> io::Result<()> {
+        self.stop.take();
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::Other, "recording thread panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.stop_and_join().ok();
+    }
+}
+
+fn record(
+    root: &impl Root,
+    mut out: impl Write,
+    interval: Duration,
+    stop_requested: mpsc::Receiver<()>,
+) -> io::Result<()> {
+    let start = Instant::now();
+    write_line(
+        &mut out,
+        &Header {
+            version: FORMAT_VERSION,
+            started_at: SystemTime::now(),
+            messages_capacity: root.messages_capacity(),
+        },
+    )?;
+    let mut last_generation = None;
+    let mut for_next_copy = None;
+    let mut tasks = Vec::new();
+    let mut messages = Vec::new();
+    loop {
+        let stop = !matches!(
+            stop_requested.recv_timeout(interval),
+            Err(mpsc::RecvTimeoutError::Timeout)
+        );
+        let generation = root.generation();
+        let tasks_changed = generation.is_none() || generation != last_generation;
+        last_generation = generation;
+        if tasks_changed {
+            root.sorted_snapshot(&mut tasks);
+        }
+        for_next_copy = Some(root.copy_new_messages(&mut messages, for_next_copy.take()));
+        if tasks_changed || !messages.is_empty() {
+            write_line(
+                &mut out,
+                &Frame {
+                    elapsed: start.elapsed(),
+                    tasks: if tasks_changed {
+                        Some(std::mem::take(&mut tasks))
+                    } else {
+                        None
+                    },
+                    messages: std::mem::take(&mut messages),
+                },
+            )?;
+        }
+        if stop {
+            return out.flush();
+        }
+    }
+}
+
+fn write_line(out: &mut impl Write, value: &impl serde::Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")
+}
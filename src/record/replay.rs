@@ -0,0 +1,274 @@
+use crate::{
+    messages::{Message, MessageCopyState, MessageRingBuffer},
+    progress::{Key, State, Task},
+    record::{Frame, Header, FORMAT_VERSION},
+    Root,
+};
+use parking_lot::Mutex;
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// The fastest possible playback speed, to which faster speeds are clamped.
+const MAX_SPEED: f32 = 1_000_000.0;
+
+/// A [`Root`](../trait.Root.html) playing back a recording made by a [`Recorder`](./struct.Recorder.html).
+///
+/// Playback starts once it's created, and times within the recording are shifted to make the current position look as if it
+/// was recorded just now.
+/// Clones share their position in the recording.
+#[derive(Clone)]
+pub struct Replay {
+    frames: Arc<Vec<Frame>>,
+    messages_capacity: usize,
+    started_at: SystemTime,
+    state: Arc<Mutex<Playback>>,
+}
+
+struct Playback {
+    speed: f32,
+    /// The position in the recording at `positioned_at`.
+    position: Duration,
+    positioned_at: Instant,
+    /// The amount of frames applied so far.
+    applied: usize,
+    /// The amount of frames whose messages were applied, which doesn't decrease when seeking backwards.
+    messages_applied: usize,
+    /// Increases whenever frames are applied or the position is changed, unlike `applied`.
+    generation: u64,
+    tasks: Vec<(Key, Task)>,
+    messages: MessageRingBuffer,
+}
+
+/// Initialization
+impl Replay {
+    /// Load the recording at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Replay> {
+        Replay::from_reader(BufReader::new(fs::File::open(path)?))
+    }
+
+    /// Load the recording from `input`.
+    pub fn from_reader(input: impl BufRead) -> io::Result<Replay> {
+        let mut lines = input.lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "recording is empty")),
+        };
+        if header.version != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "recording has format version {}, but only version {} is supported",
+                    header.version, FORMAT_VERSION
+                ),
+            ));
+        }
+        let frames = lines
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<io::Result<Vec<Frame>>>()?;
+        Ok(Replay {
+            frames: Arc::new(frames),
+            messages_capacity: header.messages_capacity,
+            started_at: header.started_at,
+            state: Arc::new(Mutex::new(Playback {
+                speed: 1.0,
+                position: Duration::default(),
+                positioned_at: Instant::now(),
+                applied: 0,
+                messages_applied: 0,
+                generation: 0,
+                tasks: Vec::new(),
+                messages: MessageRingBuffer::with_capacity(header.messages_capacity),
+            })),
+        })
+    }
+
+    /// Play back at the given `speed`, with `2.0` being twice as fast as it was recorded.
+    ///
+    /// A speed of `0.0` pauses playback, which can then be advanced deterministically with [`seek(…)`](#method.seek).
+    /// Negative speeds and `NaN` pause as well, and speeds are limited to a million times faster than recorded.
+    pub fn with_speed(self, speed: f32) -> Self {
+        self.set_speed(speed);
+        self
+    }
+}
+
+/// Playback control
+impl Replay {
+    /// Change the playback speed, see [`with_speed(…)`](#method.with_speed).
+    pub fn set_speed(&self, speed: f32) {
+        let mut state = self.state.lock();
+        state.position = state.current_position();
+        state.positioned_at = Instant::now();
+        state.speed = if speed.is_nan() {
+            0.0
+        } else {
+            speed.clamp(0.0, MAX_SPEED)
+        };
+    }
+
+    /// Continue playback from the given `position` within the recording.
+    pub fn seek(&self, position: Duration) {
+        let mut state = self.state.lock();
+        state.position = position;
+        state.positioned_at = Instant::now();
+        state.generation += 1;
+    }
+
+    /// Returns the current position within the recording.
+    pub fn position(&self) -> Duration {
+        self.state.lock().current_position()
+    }
+
+    /// Returns the time from the start of the recording to its last frame.
+    pub fn duration(&self) -> Duration {
+        self.frames.last().map(|frame| frame.elapsed).unwrap_or_default()
+    }
+
+    /// Returns true if all frames were played back.
+    pub fn is_finished(&self) -> bool {
+        let mut state = self.state.lock();
+        state.catch_up(&self.frames);
+        state.applied == self.frames.len()
+    }
+
+    /// Catch up with the current position and return the state of playback.
+    fn playback(&self) -> parking_lot::MutexGuard<'_, Playback> {
+        let mut state = self.state.lock();
+        state.catch_up(&self.frames);
+        state
+    }
+
+    /// Catch up with the current position and copy messages with `copy` into `out`, with their times shifted.
+    fn copy_shifted_messages<T>(
+        &self,
+        out: &mut Vec<Message>,
+        copy: impl FnOnce(&MessageRingBuffer, &mut Vec<Message>) -> T,
+    ) -> T {
+        let state = self.playback();
+        let res = copy(&state.messages, out);
+        let shift = state.time_shift(self.started_at);
+        for message in out.iter_mut() {
+            shift.apply(&mut message.time);
+        }
+        res
+    }
+}
+
+impl Playback {
+    fn current_position(&self) -> Duration {
+        self.position
+            .saturating_add(self.positioned_at.elapsed().mul_f32(self.speed))
+    }
+
+    /// Returns the shift making the current position of a recording started at `started_at` appear to be now.
+    fn time_shift(&self, started_at: SystemTime) -> TimeShift {
+        TimeShift {
+            from: started_at.checked_add(self.current_position()).unwrap_or(started_at),
+            to: SystemTime::now(),
+        }
+    }
+
+    /// Apply all frames up to the current position, starting over if it moved backwards.
+    ///
+    /// Messages are only ever added, thus they are kept when moving backwards and aren't added twice.
+    fn catch_up(&mut self, frames: &[Frame]) {
+        let position = self.current_position();
+        let applied = self.applied;
+        if self.applied > 0 && frames[self.applied - 1].elapsed > position {
+            self.applied = 0;
+            self.tasks.clear();
+        }
+        for frame in frames[self.applied..]
+            .iter()
+            .take_while(|frame| frame.elapsed <= position)
+        {
+            if let Some(tasks) = &frame.tasks {
+                self.tasks.clone_from(tasks);
+            }
+            if self.applied == self.messages_applied {
+                for message in &frame.messages {
+                    self.messages.push(message.clone());
+                }
+                self.messages_applied += 1;
+            }
+            self.applied += 1;
+        }
+        if self.applied != applied {
+            self.generation += 1;
+        }
+    }
+}
+
+/// Moves times by the difference between two points in time.
+struct TimeShift {
+    from: SystemTime,
+    to: SystemTime,
+}
+
+impl TimeShift {
+    fn apply(&self, time: &mut SystemTime) {
+        let shifted = match self.to.duration_since(self.from) {
+            Ok(forward) => time.checked_add(forward),
+            Err(backward) => time.checked_sub(backward.duration()),
+        };
+        if let Some(shifted) = shifted {
+            *time = shifted;
+        }
+    }
+}
+
+fn shift_task(task: &mut Task, shift: &TimeShift) {
+    let shift_time = |time: &mut SystemTime| shift.apply(time);
+    task.dropped_at.as_mut().map(shift_time);
+    if let Some(progress) = task.progress.as_mut() {
+        progress.initialized_at.as_mut().map(shift_time);
+        progress.last_advanced_at.as_mut().map(shift_time);
+        match &mut progress.state {
+            State::Blocked(_, eta) | State::Halted(_, eta) => {
+                eta.as_mut().map(shift_time);
+            }
+            State::Finished(time) | State::Failed(time) | State::Cancelled(time) => shift_time(time),
+            State::Running => {}
+        }
+    }
+}
+
+impl Root for Replay {
+    fn messages_capacity(&self) -> usize {
+        self.messages_capacity
+    }
+
+    fn num_tasks(&self) -> usize {
+        self.playback().tasks.len()
+    }
+
+    fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        let state = self.playback();
+        out.clone_from(&state.tasks);
+        let shift = state.time_shift(self.started_at);
+        for (_, task) in out.iter_mut() {
+            shift_task(task, &shift);
+        }
+    }
+
+    fn copy_messages(&self, out: &mut Vec<Message>) {
+        self.copy_shifted_messages(out, |messages, out| messages.copy_all(out));
+    }
+
+    fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
+        self.copy_shifted_messages(out, |messages, out| messages.copy_new(out, prev))
+    }
+
+    fn copy_messages_from(&self, key: &Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.copy_shifted_messages(out, |messages, out| messages.copy_from(key, include_subtree, out));
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.playback().generation)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    record::{Recorder, Replay},
+    Root, Tree,
+};
+use parking_lot::Mutex;
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn task_names(root: &impl Root) -> Vec<String> {
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    tasks.into_iter().map(|(_, task)| task.name).collect()
+}
+
+/// Record a session with a task that is added, advanced and removed, with one interval passing after each step.
+fn recording() -> Vec<u8> {
+    let interval = Duration::from_millis(20);
+    let tree = Tree::new();
+    let buf = SharedBuf::default();
+    let recorder = Recorder::to_writer(tree.clone(), buf.clone(), Duration::from_millis(1));
+    let mut task = tree.add_child("task");
+    task.init(Some(10), None);
+    std::thread::sleep(interval);
+    task.inc_by(5);
+    task.info("halfway");
+    std::thread::sleep(interval);
+    drop(task);
+    std::thread::sleep(interval);
+    recorder.stop().unwrap();
+    let recording = buf.0.lock().clone();
+    recording
+}
+
+#[test]
+fn recordings_can_be_replayed_deterministically() {
+    let replay = Replay::from_reader(&recording()[..]).unwrap().with_speed(0.0);
+    assert!(task_names(&replay).is_empty(), "nothing happened at the very start");
+    assert!(replay.duration() >= Duration::from_millis(40));
+
+    let mut steps = Vec::new();
+    let mut messages = Vec::new();
+    let mut position = Duration::default();
+    let mut last_generation = None;
+    while !replay.is_finished() {
+        position += Duration::from_millis(1);
+        replay.seek(position);
+        if replay.generation() == last_generation {
+            continue;
+        }
+        last_generation = replay.generation();
+        let mut tasks = Vec::new();
+        replay.sorted_snapshot(&mut tasks);
+        steps.push(
+            tasks
+                .first()
+                .and_then(|(_, task)| task.progress.as_ref().map(|p| p.step)),
+        );
+        replay.copy_messages(&mut messages);
+    }
+    // the task may have been recorded before it was initialised
+    let mut steps: Vec<_> = steps.into_iter().skip_while(Option::is_none).collect();
+    steps.dedup();
+    assert_eq!(steps, vec![Some(0), Some(5), None]);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message, "halfway");
+
+    replay.seek(Duration::default());
+    assert!(
+        task_names(&replay).is_empty(),
+        "seeking backwards restores earlier state"
+    );
+    replay.copy_messages(&mut messages);
+    assert_eq!(messages.len(), 1, "messages are kept, but not duplicated");
+}
+
+#[test]
+fn replays_play_back_in_real_time_unless_sped_up() {
+    let recording = recording();
+    let replay = Replay::from_reader(&recording[..]).unwrap().with_speed(1000.0);
+    std::thread::sleep(Duration::from_millis(1));
+    assert!(replay.is_finished());
+
+    for speed in [f32::INFINITY, f32::MAX, f32::NAN, f32::NEG_INFINITY] {
+        replay.set_speed(speed);
+        replay.position();
+    }
+    replay.seek(Duration::MAX);
+    replay.set_speed(f32::INFINITY);
+    assert_eq!(
+        replay.position(),
+        Duration::MAX,
+        "positions saturate instead of overflowing"
+    );
+}
+
+#[test]
+fn generations_increase_when_seeking_backwards() {
+    let replay = Replay::from_reader(&recording()[..]).unwrap().with_speed(0.0);
+    replay.seek(replay.duration());
+    let at_end = replay.generation();
+    replay.seek(Duration::default());
+    assert!(replay.generation() > at_end);
+}
+
+#[test]
+fn times_are_not_in_the_future_after_seeking_ahead() {
+    let replay = Replay::from_reader(&recording()[..]).unwrap().with_speed(0.0);
+    replay.seek(replay.duration());
+    let mut messages = Vec::new();
+    replay.copy_messages(&mut messages);
+    assert!(messages[0].time <= SystemTime::now());
+
+    let mut position = Duration::default();
+    let progress = loop {
+        position += Duration::from_millis(1);
+        replay.seek(position);
+        let mut tasks = Vec::new();
+        replay.sorted_snapshot(&mut tasks);
+        if let Some(progress) = tasks.into_iter().find_map(|(_, task)| task.progress) {
+            break progress;
+        }
+    };
+    assert!(progress.elapsed().is_some(), "the task was initialized in the past");
+}
+
+#[test]
+fn recordings_of_other_format_versions_are_rejected() {
+    let err = Replay::from_reader(
+        &br#"{"version":0,"started_at":{"secs_since_epoch":0,"nanos_since_epoch":0},"messages_capacity":20}"#[..],
+    )
+    .err()
+    .expect("version mismatch");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,168 @@
+use crate::{
+    messages::{Message, MessageCopyState, MessageRingBuffer},
+    progress::{Key, Task},
+    remote::{read_line, write_line, Hello, Request, Update, MAX_MESSAGES_CAPACITY, PROTOCOL_VERSION},
+    Root,
+};
+use parking_lot::Mutex;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::Arc,
+    time::Duration,
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::Path};
+
+/// A [`Root`](../trait.Root.html) mirroring the one of a [`Server`](./struct.Server.html), possibly in another process.
+///
+/// The mirror is updated from a background thread, which stops once the server disconnects or all clones of the client are dropped.
+/// Cancellation requests are forwarded to the server.
+#[derive(Clone)]
+pub struct Client {
+    mirror: Arc<Mutex<Mirror>>,
+    messages_capacity: usize,
+}
+
+struct Mirror {
+    tasks: Vec<(Key, Task)>,
+    messages: MessageRingBuffer,
+    generation: u64,
+    cancellations: Vec<Key>,
+    connected: bool,
+}
+
+/// Initialization
+impl Client {
+    /// Connect to the server listening on the TCP port at `address`, and ask it for changes every `update_interval`.
+    pub fn connect_tcp(address: impl ToSocketAddrs, update_interval: Duration) -> io::Result<Client> {
+        let stream = TcpStream::connect(address)?;
+        Client::from_connection(BufReader::new(stream.try_clone()?), stream, update_interval)
+    }
+
+    /// Connect to the server listening on the Unix domain socket at `path`, and ask it for changes every `update_interval`.
+    #[cfg(unix)]
+    pub fn connect_unix(path: impl AsRef<Path>, update_interval: Duration) -> io::Result<Client> {
+        let stream = UnixStream::connect(path)?;
+        Client::from_connection(BufReader::new(stream.try_clone()?), stream, update_interval)
+    }
+
+    fn from_connection(
+        mut input: impl BufRead + Send + 'static,
+        mut out: impl Write + Send + 'static,
+        update_interval: Duration,
+    ) -> io::Result<Client> {
+        let mut buf = String::new();
+        let hello: Hello = read_line(&mut input, &mut buf)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "server hung up before greeting"))?;
+        if hello.protocol != "prodash" || hello.version != PROTOCOL_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "server speaks {} protocol version {}, but only version {} is supported",
+                    hello.protocol, hello.version, PROTOCOL_VERSION
+                ),
+            ));
+        }
+        let messages_capacity = hello.messages_capacity.clamp(1, MAX_MESSAGES_CAPACITY);
+        let mirror = Arc::new(Mutex::new(Mirror {
+            tasks: Vec::new(),
+            messages: MessageRingBuffer::with_capacity(messages_capacity),
+            generation: 0,
+            cancellations: Vec::new(),
+            connected: true,
+        }));
+        std::thread::spawn({
+            let mirror = mirror.clone();
+            move || {
+                update_until_disconnected(&mirror, &mut input, &mut out, update_interval).ok();
+                let mut mirror = mirror.lock();
+                mirror.connected = false;
+                mirror.generation += 1;
+            }
+        });
+        Ok(Client {
+            mirror,
+            messages_capacity,
+        })
+    }
+}
+
+impl Client {
+    /// Returns true if the connection to the server is still alive.
+    ///
+    /// Once it's gone, the last known state of the server's tree remains available.
+    pub fn is_connected(&self) -> bool {
+        self.mirror.lock().connected
+    }
+}
+
+fn update_until_disconnected(
+    mirror: &Arc<Mutex<Mirror>>,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    update_interval: Duration,
+) -> io::Result<()> {
+    let mut buf = String::new();
+    // Stop once the only one left holding the mirror is us.
+    while Arc::strong_count(mirror) > 1 {
+        let cancellations = std::mem::take(&mut mirror.lock().cancellations);
+        for key in cancellations {
+            write_line(out, &Request::Cancel(key))?;
+        }
+        write_line(out, &Request::Update)?;
+        let Update { tasks, messages } = match read_line(input, &mut buf)? {
+            Some(update) => update,
+            None => return Ok(()),
+        };
+        {
+            let mut mirror = mirror.lock();
+            let changed = tasks.is_some() || !messages.is_empty();
+            if let Some(tasks) = tasks {
+                mirror.tasks = tasks;
+            }
+            for message in messages {
+                mirror.messages.push(message);
+            }
+            if changed {
+                mirror.generation += 1;
+            }
+        }
+        std::thread::sleep(update_interval);
+    }
+    Ok(())
+}
+
+impl Root for Client {
+    fn messages_capacity(&self) -> usize {
+        self.messages_capacity
+    }
+
+    fn num_tasks(&self) -> usize {
+        self.mirror.lock().tasks.len()
+    }
+
+    fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        out.clone_from(&self.mirror.lock().tasks);
+    }
+
+    fn copy_messages(&self, out: &mut Vec<Message>) {
+        self.mirror.lock().messages.copy_all(out);
+    }
+
+    fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
+        self.mirror.lock().messages.copy_new(out, prev)
+    }
+
+    fn copy_messages_from(&self, key: &Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.mirror.lock().messages.copy_from(key, include_subtree, out);
+    }
+
+    fn cancel(&self, key: &Key) {
+        self.mirror.lock().cancellations.push(key.clone());
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.mirror.lock().generation)
+    }
+}
@@ -0,0 +1,109 @@
+/*!
+Serve any [`Root`](../trait.Root.html) over a TCP port or Unix domain socket, and mirror it in another process
+with a [`Client`](./struct.Client.html), which implements `Root` itself and thus works with all renderers.
+
+Connections are neither authenticated nor encrypted, so only listen on the loopback interface.
+
+```rust,no_run
+# fn main() -> std::io::Result<()> {
+use prodash::remote::{Client, Server};
+use std::time::Duration;
+
+// in the process doing the work
+let tree = prodash::Tree::new();
+let _server = Server::tcp(tree.clone(), "127.0.0.1:7878")?;
+
+// in the process showing the progress
+let client = Client::connect_tcp("127.0.0.1:7878", Duration::from_millis(100))?;
+# Ok(())
+# }
+```
+
+## Protocol
+
+Both sides exchange JSON objects, one per line. Once connected, the server greets the client with its
+protocol `version`, which the client rejects if it doesn't match [`PROTOCOL_VERSION`](./constant.PROTOCOL_VERSION.html).
+From then on, the client sends requests to which the server responds, or which it just acts on.
+*/
+use crate::{
+    messages::Message,
+    progress::{Key, Task},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{self, BufRead, Read, Write};
+
+mod client;
+pub use client::Client;
+
+mod server;
+pub use server::Server;
+
+#[cfg(all(test, feature = "progress-tree"))]
+mod tests;
+
+/// The version of the protocol spoken between servers and clients, which must be the same on both sides.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The largest amount of messages a client keeps, no matter how many the server claims to keep.
+const MAX_MESSAGES_CAPACITY: usize = 10_000;
+/// The longest line either side reads, including the newline, to not run out of memory if the other side never sends one.
+const MAX_LINE_LENGTH: u64 = 64 * 1024 * 1024;
+
+/// The first line sent by the server.
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    protocol: String,
+    version: u32,
+    messages_capacity: usize,
+}
+
+impl Hello {
+    fn new(messages_capacity: usize) -> Self {
+        Hello {
+            protocol: "prodash".into(),
+            version: PROTOCOL_VERSION,
+            messages_capacity,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Respond with an `Update`.
+    Update,
+    /// Cancel the task with the given key, without responding.
+    Cancel(Key),
+}
+
+/// Everything that changed since the previous update.
+#[derive(Serialize, Deserialize)]
+struct Update {
+    /// All tasks, unless they didn't change.
+    tasks: Option<Vec<(Key, Task)>>,
+    /// All messages sent since the previous update, as far as they fit into the message buffer.
+    messages: Vec<Message>,
+}
+
+fn write_line(out: &mut impl Write, value: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+/// Read the next line into `buf` and deserialize it, or return `None` if the other side hung up.
+///
+/// Lines longer than `MAX_LINE_LENGTH` are rejected.
+fn read_line<T: DeserializeOwned>(input: &mut impl BufRead, buf: &mut String) -> io::Result<Option<T>> {
+    buf.clear();
+    let read = input.take(MAX_LINE_LENGTH).read_line(buf)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read as u64 == MAX_LINE_LENGTH && !buf.ends_with('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line exceeds the maximum length of {} bytes", MAX_LINE_LENGTH),
+        ));
+    }
+    Ok(Some(serde_json::from_str(buf)?))
+}
@@ -0,0 +1,241 @@
+use crate::{
+    remote::{read_line, write_line, Hello, Request, Update},
+    Root,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+};
+
+/// A server making a [`Root`](../trait.Root.html) available to [`Client`s](./struct.Client.html),
+/// accepting connections from a background thread until it is dropped.
+///
+/// Each connection is served by its own thread, which keeps running until its client disconnects or the server is dropped.
+///
+/// **Note** that there is no authentication or encryption, so anyone who can connect to the server can see all tasks and messages.
+/// Thus it's only safe to listen on the loopback interface, like `127.0.0.1`, or on Unix domain sockets with suitable permissions.
+/// For the same reason, clients can only cancel tasks if [allowed explicitly](#method.allow_cancellation).
+pub struct Server {
+    address: Address,
+    state: Arc<State>,
+}
+
+enum Address {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// The state shared between the server and the threads serving it.
+#[derive(Default)]
+struct State {
+    cancellation_allowed: AtomicBool,
+    connections: Mutex<Connections>,
+}
+
+/// The connections being served, to be closed once the server is dropped.
+#[derive(Default)]
+struct Connections {
+    stopped: bool,
+    next_id: u64,
+    open: BTreeMap<u64, (Stream, JoinHandle<()>)>,
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn shutdown(&self) {
+        match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Both).ok(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Both).ok(),
+        };
+    }
+}
+
+/// Initialization
+impl Server {
+    /// Serve `root` on the TCP port at `address`, like `127.0.0.1:0` to pick any free port on the local machine.
+    ///
+    /// See the [`Server`](./struct.Server.html) documentation for why the address should be on the loopback interface.
+    pub fn tcp(root: impl Root + Clone + Send + 'static, address: impl ToSocketAddrs) -> io::Result<Server> {
+        let listener = TcpListener::bind(address)?;
+        let state = Arc::new(State::default());
+        let address = listener.local_addr()?;
+        std::thread::spawn({
+            let state = state.clone();
+            move || {
+                for stream in listener.incoming().flatten() {
+                    let served = match (stream.try_clone(), stream.try_clone()) {
+                        (Ok(reader), Ok(closer)) => {
+                            serve_connection(&state, root.clone(), Stream::Tcp(closer), reader, stream)
+                        }
+                        _ => true,
+                    };
+                    if !served {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Server {
+            address: Address::Tcp(address),
+            state,
+        })
+    }
+
+    /// Serve `root` on a Unix domain socket created at `path`, which must not exist yet.
+    ///
+    /// The socket is removed once the server is dropped.
+    #[cfg(unix)]
+    pub fn unix(root: impl Root + Clone + Send + 'static, path: impl AsRef<Path>) -> io::Result<Server> {
+        let listener = UnixListener::bind(path.as_ref())?;
+        let state = Arc::new(State::default());
+        std::thread::spawn({
+            let state = state.clone();
+            move || {
+                for stream in listener.incoming().flatten() {
+                    let served = match (stream.try_clone(), stream.try_clone()) {
+                        (Ok(reader), Ok(closer)) => {
+                            serve_connection(&state, root.clone(), Stream::Unix(closer), reader, stream)
+                        }
+                        _ => true,
+                    };
+                    if !served {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Server {
+            address: Address::Unix(path.as_ref().to_owned()),
+            state,
+        })
+    }
+}
+
+impl Server {
+    /// Returns the address the server is listening on if it serves over TCP, which is useful if the port was picked automatically.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match self.address {
+            Address::Tcp(address) => Some(address),
+            #[cfg(unix)]
+            Address::Unix(_) => None,
+        }
+    }
+
+    /// If `allowed` is true, let clients cancel tasks, which is forbidden by default as anyone who can connect could do it.
+    ///
+    /// Cancellation requests of clients are ignored otherwise.
+    pub fn allow_cancellation(&self, allowed: bool) {
+        self.state.cancellation_allowed.store(allowed, Ordering::Relaxed);
+    }
+}
+
+/// Stops accepting connections, and closes all open ones, waiting for the threads serving them to finish.
+impl Drop for Server {
+    fn drop(&mut self) {
+        let open = {
+            let mut connections = self.state.connections.lock();
+            connections.stopped = true;
+            std::mem::take(&mut connections.open)
+        };
+        for (stream, _) in open.values() {
+            stream.shutdown();
+        }
+        for (_, (_, thread)) in open {
+            thread.join().ok();
+        }
+        // wake up the thread accepting connections so it sees it should stop
+        match &self.address {
+            Address::Tcp(address) => {
+                TcpStream::connect(address).ok();
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                UnixStream::connect(path).ok();
+                std::fs::remove_file(path).ok();
+            }
+        }
+    }
+}
+
+/// Serve a new connection from its own thread, unless the server was stopped, in which case false is returned.
+fn serve_connection(
+    state: &Arc<State>,
+    root: impl Root + Send + 'static,
+    stream: Stream,
+    reader: impl io::Read + Send + 'static,
+    writer: impl Write + Send + 'static,
+) -> bool {
+    let mut connections = state.connections.lock();
+    if connections.stopped {
+        return false;
+    }
+    let id = connections.next_id;
+    connections.next_id += 1;
+    let thread = std::thread::spawn({
+        let state = state.clone();
+        move || {
+            // Clients going away or sending garbage only affect their own connection.
+            serve(&root, &state, BufReader::new(reader), writer).ok();
+            state.connections.lock().open.remove(&id);
+        }
+    });
+    connections.open.insert(id, (stream, thread));
+    true
+}
+
+/// Respond to all requests read from `input` by writing to `out`, until the client disconnects.
+fn serve(root: &impl Root, state: &State, mut input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+    write_line(&mut out, &Hello::new(root.messages_capacity()))?;
+    let mut buf = String::new();
+    let mut last_generation = None;
+    let mut for_next_copy = None;
+    let mut messages = Vec::new();
+    while let Some(request) = read_line(&mut input, &mut buf)? {
+        match request {
+            Request::Cancel(key) => {
+                if state.cancellation_allowed.load(Ordering::Relaxed) {
+                    root.cancel(&key)
+                }
+            }
+            Request::Update => {
+                let generation = root.generation();
+                let tasks = if generation.is_none() || generation != last_generation {
+                    let mut tasks = Vec::new();
+                    root.sorted_snapshot(&mut tasks);
+                    Some(tasks)
+                } else {
+                    None
+                };
+                last_generation = generation;
+                for_next_copy = Some(root.copy_new_messages(&mut messages, for_next_copy.take()));
+                write_line(
+                    &mut out,
+                    &Update {
+                        tasks,
+                        messages: std::mem::take(&mut messages),
+                    },
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use crate::{
+    remote::{Client, Server, PROTOCOL_VERSION},
+    Root, Tree,
+};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(5), "condition not met in time");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn task_names(root: &impl Root) -> Vec<String> {
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    tasks.into_iter().map(|(_, task)| task.name).collect()
+}
+
+#[test]
+fn clients_mirror_tasks_and_messages_and_forward_cancellations() {
+    let tree = Tree::new();
+    let server = Server::tcp(tree.clone(), "127.0.0.1:0").unwrap();
+    server.allow_cancellation(true);
+    let client = Client::connect_tcp(server.local_addr().unwrap(), Duration::from_millis(1)).unwrap();
+    assert_eq!(client.messages_capacity(), tree.messages_capacity());
+
+    let mut task = tree.add_child("remote task");
+    task.init(Some(10), None);
+    task.inc_by(5);
+    task.info("hello from afar");
+    wait_for(|| task_names(&client) == vec!["remote task"]);
+
+    let mut snapshot = Vec::new();
+    wait_for(|| {
+        client.sorted_snapshot(&mut snapshot);
+        snapshot[0].1.progress.as_ref().map(|p| p.step) == Some(5)
+    });
+    let mut messages = Vec::new();
+    wait_for(|| {
+        client.copy_messages(&mut messages);
+        !messages.is_empty()
+    });
+    assert_eq!(messages[0].message, "hello from afar");
+
+    client.cancel(&snapshot[0].0);
+    wait_for(|| task.is_cancelled());
+
+    let generation = client.generation();
+    drop(task);
+    wait_for(|| client.generation() != generation && task_names(&client).is_empty());
+}
+
+#[test]
+fn cancellation_requires_permission_and_dropped_servers_close_all_connections() {
+    let tree = Tree::new();
+    let server = Server::tcp(tree.clone(), "127.0.0.1:0").unwrap();
+    let client = Client::connect_tcp(server.local_addr().unwrap(), Duration::from_millis(1)).unwrap();
+    let task = tree.add_child("task");
+    wait_for(|| task_names(&client) == vec!["task"]);
+
+    let mut snapshot = Vec::new();
+    client.sorted_snapshot(&mut snapshot);
+    client.cancel(&snapshot[0].0);
+    // requests are handled in order, so once this task shows up the cancellation request was handled as well
+    let _second = tree.add_child("second");
+    wait_for(|| task_names(&client).len() == 2);
+    assert!(!task.is_cancelled(), "cancellation is ignored unless allowed");
+
+    drop(server);
+    assert!(
+        wait_until(|| !client.is_connected()),
+        "connections are closed along with the server"
+    );
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+    while !condition() {
+        if start.elapsed() > Duration::from_secs(5) {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    true
+}
+
+#[test]
+fn clients_notice_when_the_server_goes_away() {
+    let tree = Tree::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server_side = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(
+            stream,
+            r#"{{"protocol":"prodash","version":{},"messages_capacity":{}}}"#,
+            PROTOCOL_VERSION,
+            tree.messages_capacity()
+        )
+        .unwrap();
+    });
+    let client = Client::connect_tcp(address, Duration::from_millis(1)).unwrap();
+    server_side.join().unwrap();
+    wait_for(|| !client.is_connected());
+}
+
+#[test]
+fn clients_limit_the_amount_of_messages_they_keep() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(
+            stream,
+            r#"{{"protocol":"prodash","version":{},"messages_capacity":{}}}"#,
+            PROTOCOL_VERSION,
+            usize::MAX
+        )
+        .unwrap();
+    });
+    let client = Client::connect_tcp(address, Duration::from_millis(1)).unwrap();
+    assert_eq!(client.messages_capacity(), 10_000);
+}
+
+#[test]
+fn clients_keep_at_least_one_message() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(
+            stream,
+            r#"{{"protocol":"prodash","version":{},"messages_capacity":0}}"#,
+            PROTOCOL_VERSION
+        )
+        .unwrap();
+        let mut input = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        input.read_line(&mut line).unwrap();
+        writeln!(
+            stream,
+            r#"{{"tasks":null,"messages":[{{"time":{{"secs_since_epoch":0,"nanos_since_epoch":0}},"level":"Info","origin":"server","key":[],"path":[],"message":"hi"}}]}}"#
+        )
+        .unwrap();
+        input.read_line(&mut line).ok();
+    });
+    let client = Client::connect_tcp(address, Duration::from_millis(1)).unwrap();
+    assert_eq!(client.messages_capacity(), 1);
+    let mut messages = Vec::new();
+    wait_for(|| {
+        client.copy_messages(&mut messages);
+        !messages.is_empty()
+    });
+    assert!(client.is_connected(), "storing messages works");
+}
+
+#[test]
+fn overly_long_lines_are_rejected() {
+    let mut buf = String::new();
+    let err = super::read_line::<super::Request>(&mut BufReader::new(std::io::repeat(b'x')), &mut buf)
+        .err()
+        .expect("too long");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn clients_reject_other_protocol_versions() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(
+            stream,
+            r#"{{"protocol":"prodash","version":{},"messages_capacity":20}}"#,
+            PROTOCOL_VERSION + 1
+        )
+        .unwrap();
+    });
+    let err = Client::connect_tcp(address, Duration::from_millis(1))
+        .err()
+        .expect("version mismatch");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_sockets_can_be_used_instead_of_tcp() {
+    let path = std::env::temp_dir().join(format!("prodash-remote-test-{}.sock", std::process::id()));
+    let tree = Tree::new();
+    let server = Server::unix(tree.clone(), &path).unwrap();
+    let client = Client::connect_unix(&path, Duration::from_millis(1)).unwrap();
+    let _task = tree.add_child("over a socket");
+    wait_for(|| task_names(&client) == vec!["over a socket"]);
+    drop(server);
+    assert!(!path.exists(), "the socket is removed along with the server");
+}
@@ -0,0 +1,97 @@
+use crate::{
+    messages::{Message, MessageCopyState, MessageLevel},
+    progress::{self, Change, Key, SnapshotDiff, State as TaskState, Task, Value},
+    Root,
+};
+use serde::Serialize;
+use std::{io, ops::RangeInclusive};
+
+#[derive(Default)]
+pub struct State {
+    tree: Vec<(Key, Task)>,
+    previous_tree: Vec<(Key, Task)>,
+    messages: Vec<Message>,
+    for_next_copy: Option<MessageCopyState>,
+    /// The generation of the tree at the last tick, if known.
+    generation: Option<u64>,
+}
+
+pub struct Options {
+    pub level_filter: Option<RangeInclusive<progress::key::Level>>,
+    pub min_message_level: Option<MessageLevel>,
+    pub keep_running_if_progress_is_empty: bool,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    TaskAdded { key: &'a Key, task: &'a Task },
+    TaskRemoved { key: &'a Key },
+    TaskRenamed { key: &'a Key, name: &'a str },
+    TaskProgressed { key: &'a Key, progress: &'a Value },
+    TaskStateChanged { key: &'a Key, state: TaskState },
+    TaskAltered { key: &'a Key, task: &'a Task },
+    Message(&'a Message),
+}
+
+fn write_event(out: &mut impl io::Write, event: &Event<'_>) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, event)?;
+    out.write_all(b"\n")
+}
+
+pub fn all(out: &mut impl io::Write, progress: &impl Root, state: &mut State, config: &Options) -> io::Result<()> {
+    let generation = progress.generation();
+    if generation.is_some() && generation == state.generation {
+        return Ok(());
+    }
+    state.generation = generation;
+
+    std::mem::swap(&mut state.tree, &mut state.previous_tree);
+    progress.sorted_snapshot(&mut state.tree);
+    if !config.keep_running_if_progress_is_empty && state.tree.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::Other, "stop as progress is empty"));
+    }
+
+    state.for_next_copy = Some(progress.copy_new_messages(&mut state.messages, state.for_next_copy.take()));
+    for message in state.messages.iter().filter(|m| match config.min_message_level {
+        Some(min_level) => m.level >= min_level,
+        None => true,
+    }) {
+        write_event(out, &Event::Message(message))?;
+    }
+
+    let level_range = config
+        .level_filter
+        .clone()
+        .unwrap_or(RangeInclusive::new(0, progress::key::Level::MAX));
+    for (key, change) in
+        SnapshotDiff::new(&state.previous_tree, &state.tree).filter(|(key, _)| level_range.contains(&key.level()))
+    {
+        match change {
+            Change::Added(task) => write_event(out, &Event::TaskAdded { key, task })?,
+            Change::Removed(_) => write_event(out, &Event::TaskRemoved { key })?,
+            Change::Renamed { to, .. } => write_event(out, &Event::TaskRenamed { key, name: to })?,
+            Change::Progressed { from, to } => {
+                if let Some(to) = to {
+                    let state_changed = matches!(from, Some(from) if from.state != to.state);
+                    if !matches!(from, Some(from) if !progressed(from, to)) {
+                        write_event(out, &Event::TaskProgressed { key, progress: to })?;
+                    }
+                    if state_changed {
+                        write_event(out, &Event::TaskStateChanged { key, state: to.state })?;
+                    }
+                }
+            }
+            Change::Altered { to, .. } => write_event(out, &Event::TaskAltered { key, task: to })?,
+        }
+    }
+    out.flush()
+}
+
+/// Returns true if anything but the state differs between `from` and `to`.
+fn progressed(from: &Value, to: &Value) -> bool {
+    from.step != to.step
+        || from.done_at != to.done_at
+        || from.initialized_at != to.initialized_at
+        || from.last_advanced_at != to.last_advanced_at
+}
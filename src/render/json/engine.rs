@@ -0,0 +1,132 @@
+use crate::{messages::MessageLevel, progress, render::json::draw, Root};
+use std::{io, ops::RangeInclusive, time::Duration};
+
+/// Configure the JSON renderer.
+#[derive(Clone)]
+pub struct Options {
+    /// If set, specify all levels of tasks to emit events for. Otherwise events for all tasks are emitted.
+    pub level_filter: Option<RangeInclusive<progress::key::Level>>,
+
+    /// If set, _(default: None)_, only messages of the given level or a more severe one are emitted.
+    pub min_message_level: Option<MessageLevel>,
+
+    /// The amount of times per second to look for changes. If below 1.0, it determines the amount of seconds in between.
+    ///
+    /// All changes within that time are coalesced, thus a task can progress many times but will only cause one event.
+    /// Messages are never lost as long as they fit into the message buffer of the progress tree.
+    pub frames_per_second: f32,
+
+    /// If true (default: true), we will keep waiting for progress even after we encountered an empty list of progress items.
+    pub keep_running_if_progress_is_empty: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            level_filter: None,
+            min_message_level: None,
+            frames_per_second: 4.0,
+            keep_running_if_progress_is_empty: true,
+        }
+    }
+}
+
+/// A handle to the render thread, which when dropped will instruct it to stop emitting events.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<io::Result<()>>>,
+    connection: std::sync::mpsc::SyncSender<Event>,
+    disconnected: bool,
+}
+
+impl JoinHandle {
+    /// `detach()` and `forget()` to remove any effects associated with this handle.
+    pub fn detach(mut self) {
+        self.disconnect();
+        self.forget();
+    }
+    /// Remove the handles capability to instruct the render thread to stop.
+    pub fn disconnect(&mut self) {
+        self.disconnected = true;
+    }
+    /// Remove the handles capability to `join()` by forgetting the threads handle
+    pub fn forget(&mut self) {
+        self.inner.take();
+    }
+    /// Wait for the thread to shutdown naturally, for example because there is no more progress to report
+    pub fn wait(mut self) {
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+    /// Send the shutdown signal right after emitting all remaining events
+    pub fn shutdown(&mut self) {
+        if !self.disconnected {
+            self.connection.send(Event::Tick).ok();
+            self.connection.send(Event::Quit).ok();
+        }
+    }
+    /// Send the signal to shutdown and wait for the thread to be shutdown.
+    pub fn shutdown_and_wait(mut self) {
+        self.shutdown();
+        self.wait();
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+}
+
+#[derive(Debug)]
+enum Event {
+    Tick,
+    Quit,
+}
+
+/// Write all changes to `progress` as newline-delimited JSON into `out` from a background thread,
+/// as described in the [module documentation](./index.html).
+pub fn render(
+    mut out: impl io::Write + Send + 'static,
+    progress: impl Root + Send + 'static,
+    Options {
+        level_filter,
+        min_message_level,
+        frames_per_second,
+        keep_running_if_progress_is_empty,
+    }: Options,
+) -> JoinHandle {
+    let config = draw::Options {
+        level_filter,
+        min_message_level,
+        keep_running_if_progress_is_empty,
+    };
+    let (event_send, event_recv) = std::sync::mpsc::sync_channel::<Event>(1);
+
+    let handle = std::thread::spawn({
+        let tick_send = event_send.clone();
+        move || {
+            let mut state = draw::State::default();
+            let secs = 1.0 / frames_per_second;
+            let _ticker = std::thread::spawn(move || loop {
+                if tick_send.send(Event::Tick).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_secs_f32(secs));
+            });
+
+            for event in event_recv {
+                match event {
+                    Event::Tick => draw::all(&mut out, &progress, &mut state, &config)?,
+                    Event::Quit => break,
+                }
+            }
+            Ok(())
+        }
+    });
+
+    JoinHandle {
+        inner: Some(handle),
+        connection: event_send,
+        disconnected: false,
+    }
+}
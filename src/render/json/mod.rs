@@ -0,0 +1,26 @@
+/*!
+A renderer writing newline-delimited JSON for consumption by other programs, one event per line.
+
+Each event is an object whose `event` field names its kind. Tasks are identified by their `key`, an array of IDs
+of the task and all of its parents.
+
+* `task_added` - a new task with its `key` and `task`, which includes its `name`, `progress`, `dropped_at` and `attributes`.
+* `task_removed` - the task with the given `key` is gone.
+* `task_renamed` - the task with the given `key` has a new `name`.
+* `task_progressed` - the `progress` of the task with the given `key` changed, for example its `step`.
+* `task_state_changed` - the task with the given `key` has a new `state`, like `"Running"` or `{"Finished": …}`.
+* `task_altered` - anything else changed about the task with the given `key`, which is sent with its `task` in full.
+* `message` - a message with all its fields, like `level`, `origin` and `message`.
+
+```json
+{"event":"task_added","key":[1],"task":{"name":"fetch","progress":null,"dropped_at":null,"attributes":{}}}
+{"event":"message","time":{"secs_since_epoch":1602000000,"nanos_since_epoch":0},"level":"Info","origin":"fetch","key":[1],"path":["fetch"],"message":"connecting"}
+```
+*/
+mod draw;
+mod engine;
+
+pub use engine::*;
+
+#[cfg(all(test, feature = "progress-tree"))]
+mod tests;
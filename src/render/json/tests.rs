@@ -0,0 +1,92 @@
+use super::draw;
+use crate::Tree;
+
+fn events(out: &mut Vec<u8>) -> Vec<serde_json::Value> {
+    let events = out
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).expect("each line to be valid JSON"))
+        .collect();
+    out.clear();
+    events
+}
+
+fn kinds(events: &[serde_json::Value]) -> Vec<&str> {
+    events
+        .iter()
+        .map(|e| e["event"].as_str().expect("event field to be set"))
+        .collect()
+}
+
+#[test]
+fn only_changes_since_the_previous_tick_are_written() {
+    let root = Tree::new();
+    let mut task = root.add_child("fetch");
+    let config = draw::Options {
+        level_filter: None,
+        min_message_level: None,
+        keep_running_if_progress_is_empty: true,
+    };
+    let mut state = draw::State::default();
+    let mut out = Vec::new();
+
+    draw::all(&mut out, &root, &mut state, &config).unwrap();
+    let added = events(&mut out);
+    assert_eq!(kinds(&added), vec!["task_added"]);
+    assert_eq!(added[0]["key"], serde_json::json!([0]));
+    assert_eq!(added[0]["task"]["name"], "fetch");
+
+    draw::all(&mut out, &root, &mut state, &config).unwrap();
+    assert!(events(&mut out).is_empty(), "nothing changed");
+
+    task.init(Some(10), None);
+    task.inc_by(3);
+    task.info("connecting");
+    draw::all(&mut out, &root, &mut state, &config).unwrap();
+    let progressed = events(&mut out);
+    assert_eq!(kinds(&progressed), vec!["message", "task_progressed"]);
+    assert_eq!(progressed[0]["message"], "connecting");
+    assert_eq!(progressed[1]["progress"]["step"], 3);
+
+    task.halted("waiting", None);
+    draw::all(&mut out, &root, &mut state, &config).unwrap();
+    assert_eq!(kinds(&events(&mut out)), vec!["task_state_changed"]);
+
+    drop(task);
+    draw::all(&mut out, &root, &mut state, &config).unwrap();
+    assert_eq!(kinds(&events(&mut out)), vec!["task_removed"]);
+}
+
+#[test]
+fn shutting_down_writes_remaining_changes() {
+    use parking_lot::Mutex;
+    use std::{io, sync::Arc};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let root = Tree::new();
+    let buf = SharedBuf::default();
+    let handle = super::render(
+        buf.clone(),
+        root.clone(),
+        super::Options {
+            frames_per_second: 0.1,
+            ..Default::default()
+        },
+    );
+    let _task = root.add_child("late");
+    handle.shutdown_and_wait();
+    let events = events(&mut buf.0.lock());
+    assert_eq!(events.last().expect("at least one event")["task"]["name"], "late");
+}
@@ -1,6 +1,6 @@
 use crate::{
     messages::{Message, MessageCopyState, MessageLevel},
-    progress::{self, Value},
+    progress::{self, Change, SnapshotDiff, Value},
     unit, Root, Throughput,
 };
 use crosstermion::{
@@ -13,6 +13,10 @@ use unicode_width::UnicodeWidthStr;
 #[derive(Default)]
 pub struct State {
     tree: Vec<(progress::Key, progress::Task)>,
+    /// The tree as it was drawn during the previous tick, if progress was drawn at all.
+    drawn_tree: Option<Vec<(progress::Key, progress::Task)>>,
+    /// The progress midpoint the lines of `drawn_tree` were drawn with.
+    drawn_midpoint: Option<u16>,
     messages: Vec<Message>,
     for_next_copy: Option<MessageCopyState>,
     /// The size of the message origin, tracking the terminal height so things potentially off screen don't influence width anymore.
@@ -26,6 +30,7 @@ pub struct State {
 
 pub struct Options {
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
+    pub min_message_level: Option<MessageLevel>,
     pub terminal_dimensions: (u16, u16),
     pub keep_running_if_progress_is_empty: bool,
     pub output_is_terminal: bool,
@@ -45,7 +50,9 @@ fn messages(
     fn to_color(level: MessageLevel) -> Color {
         use crate::messages::MessageLevel::*;
         match level {
+            Debug => Color::Blue,
             Info => Color::White,
+            Warning => Color::Yellow,
             Success => Color::Green,
             Failure => Color::Red,
         }
@@ -57,6 +64,7 @@ fn messages(
         level,
         origin,
         message,
+        ..
     } in &state.messages
     {
         tokens.clear();
@@ -107,11 +115,18 @@ pub fn all(
     state: &mut State,
     config: &Options,
 ) -> io::Result<()> {
+    let mut drawn_tree = state.drawn_tree.take();
+    if let Some(drawn_tree) = drawn_tree.as_mut() {
+        std::mem::swap(drawn_tree, &mut state.tree);
+    }
     progress.sorted_snapshot(&mut state.tree);
     if !config.keep_running_if_progress_is_empty && state.tree.is_empty() {
         return Err(io::Error::new(io::ErrorKind::Other, "stop as progress is empty"));
     }
     state.for_next_copy = Some(progress.copy_new_messages(&mut state.messages, state.for_next_copy.take()));
+    if let Some(min_level) = config.min_message_level {
+        state.messages.retain(|m| m.level >= min_level);
+    }
     messages(
         out,
         state,
@@ -119,6 +134,7 @@ pub fn all(
         config.terminal_dimensions.1 as usize,
         config.timestamp,
     )?;
+    let messages_written = !state.messages.is_empty();
 
     if show_progress && config.output_is_terminal {
         if let Some(tp) = state.throughput.as_mut() {
@@ -133,6 +149,24 @@ pub fn all(
             .iter()
             .filter(|(k, _)| level_range.contains(&k.level()))
             .count();
+        // Only lines of changed tasks are rewritten, unless lines moved or may look different for other reasons.
+        let changed_keys = drawn_tree
+            .as_ref()
+            .filter(|_| !messages_written && state.throughput.is_none())
+            .filter(|_| state.drawn_midpoint == state.last_progress_midpoint)
+            .and_then(|drawn_tree| {
+                let mut changed_keys = Vec::new();
+                for (key, change) in SnapshotDiff::new(drawn_tree, &state.tree) {
+                    match change {
+                        Change::Added(_) | Change::Removed(_) if level_range.contains(&key.level()) => return None,
+                        Change::Added(_) | Change::Removed(_) => {}
+                        Change::Renamed { .. } | Change::Progressed { .. } | Change::Altered { .. } => {
+                            changed_keys.push(key.clone())
+                        }
+                    }
+                }
+                Some(changed_keys)
+            });
         if state.blocks_per_line.len() < lines_to_be_drawn {
             state.blocks_per_line.resize(lines_to_be_drawn, 0);
         }
@@ -159,6 +193,13 @@ pub fn all(
                 )
                 .unwrap_or(0),
             );
+            let unchanged = !shows_time(value)
+                && matches!(&changed_keys, Some(changed_keys) if changed_keys.binary_search(key).is_err());
+            if unchanged {
+                // moving to the next line keeps what's already there
+                writeln!(out)?;
+                continue;
+            }
             write!(out, "{}", ANSIStrings(tokens.as_slice()))?;
 
             **blocks_in_last_iteration = newline_with_overdraw(out, &tokens, **blocks_in_last_iteration)?;
@@ -166,7 +207,9 @@ pub fn all(
         if let Some(tp) = state.throughput.as_mut() {
             tp.reconcile(&state.tree);
         }
+        state.drawn_midpoint = state.last_progress_midpoint;
         state.last_progress_midpoint = Some(max_midpoint);
+        state.drawn_tree = Some(drawn_tree.unwrap_or_default());
         // overwrite remaining lines that we didn't touch naturally
         let lines_drawn = lines_to_be_drawn;
         if state.blocks_per_line.len() > lines_drawn {
@@ -183,6 +226,11 @@ pub fn all(
     Ok(())
 }
 
+/// Returns true if the line of `task` changes with time alone, as it shows the time elapsed while the task is still going.
+fn shows_time(task: &progress::Task) -> bool {
+    matches!(&task.progress, Some(progress) if progress.initialized_at.is_some() && !progress.state.is_terminal())
+}
+
 /// Must be called directly after `tokens` were drawn, without newline. Takes care of adding the newline.
 fn newline_with_overdraw(
     out: &mut impl io::Write,
@@ -264,9 +312,16 @@ fn progress_style(p: &Value) -> Style {
         .normal(),
         Halted(_, _) => Color::Red.dimmed(),
         Blocked(_, _) => Color::Red.normal(),
+        Finished(_) => Color::Green.normal(),
+        Failed(_) => Color::Red.bold(),
+        Cancelled(_) => Color::White.dimmed(),
     }
 }
 
+fn format_seconds(duration: std::time::Duration) -> humantime::FormattedDuration {
+    humantime::format_duration(std::time::Duration::from_secs(duration.as_secs()))
+}
+
 fn format_progress<'a>(
     key: &progress::Key,
     value: &'a progress::Task,
@@ -279,11 +334,24 @@ fn format_progress<'a>(
     let mut brush = color::Brush::new(colored);
     buf.clear();
 
-    buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level() as usize)));
+    buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level())));
+    let lingering = value.dropped_at.is_some();
     match value.progress.as_ref() {
         Some(progress) => {
-            let style = progress_style(&progress);
-            buf.push(brush.style(Color::Cyan.bold()).paint(&value.name));
+            let style = if lingering {
+                progress_style(progress).dimmed()
+            } else {
+                progress_style(progress)
+            };
+            buf.push(
+                brush
+                    .style(if lingering {
+                        Color::Cyan.dimmed()
+                    } else {
+                        Color::Cyan.bold()
+                    })
+                    .paint(&value.name),
+            );
             buf.push(" ".into());
 
             let pre_unit = buf.len();
@@ -302,6 +370,20 @@ fn format_progress<'a>(
                     }));
                 }
             }
+            let eta = progress
+                .eta(throughput)
+                .filter(|_| !matches!(&progress.unit, Some(unit) if unit.shows_eta()));
+            let timing = match (progress.elapsed(), eta) {
+                (Some(elapsed), Some(eta)) => {
+                    Some(format!("{}, {} left", format_seconds(elapsed), format_seconds(eta)))
+                }
+                (Some(elapsed), None) => Some(format_seconds(elapsed).to_string()),
+                (None, Some(eta)) => Some(format!("{} left", format_seconds(eta))),
+                (None, None) => None,
+            };
+            if let Some(timing) = timing {
+                buf.push(values_brush.paint(format!(" ({})", timing)));
+            }
             let desired_midpoint = block_count_sans_ansi_codes(buf.as_slice());
             let actual_midpoint = if let Some(midpoint) = midpoint {
                 let padding = midpoint.saturating_sub(desired_midpoint);
@@ -323,7 +405,15 @@ fn format_progress<'a>(
         }
         None => {
             // headline only - FIXME: would have to truncate it if it is too long for the line…
-            buf.push(brush.style(Color::White.bold()).paint(&value.name));
+            buf.push(
+                brush
+                    .style(if lingering {
+                        Color::White.dimmed()
+                    } else {
+                        Color::White.bold()
+                    })
+                    .paint(&value.name),
+            );
             None
         }
     }
@@ -1,9 +1,14 @@
-use crate::{progress, render::line::draw, Root, Throughput};
+use crate::{
+    messages::MessageLevel,
+    progress,
+    render::{is_idle, line::draw},
+    Root, Throughput,
+};
 use std::{
     io,
     ops::RangeInclusive,
     sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[derive(Clone)]
@@ -43,6 +48,11 @@ pub struct Options {
     /// This is useful to filter out high-noise lower level progress items in the tree.
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
 
+    /// If set, _(default: None)_, only messages of the given level or a more severe one are shown.
+    ///
+    /// This is useful to hide `Debug` messages, for example.
+    pub min_message_level: Option<MessageLevel>,
+
     /// If set, progress will only actually be shown after the given duration. Log messages will always be shown without delay.
     ///
     /// This option can be useful to not enforce progress for short actions, causing it to flicker.
@@ -59,6 +69,12 @@ pub struct Options {
     /// Please note that you should add at least one item to the `prodash::Tree` before launching the application or else
     /// risk a race causing nothing to be rendered at all.
     pub keep_running_if_progress_is_empty: bool,
+
+    /// If set, _(default: 1s)_, frames are skipped while the [generation](../../trait.Root.html#method.generation)
+    /// of the progress tree doesn't change, but only for up to the given duration to keep durations and throughput current.
+    ///
+    /// If unset, a frame is drawn on every tick.
+    pub max_idle_redraw_interval: Option<Duration>,
 }
 
 impl Default for Options {
@@ -70,10 +86,12 @@ impl Default for Options {
             terminal_dimensions: (80, 20),
             hide_cursor: false,
             level_filter: None,
+            min_message_level: None,
             initial_delay: None,
             frames_per_second: 6.0,
             throughput: false,
             keep_running_if_progress_is_empty: true,
+            max_idle_redraw_interval: Some(Duration::from_secs(1)),
         }
     }
 }
@@ -141,12 +159,14 @@ pub fn render(
         colored,
         timestamp,
         level_filter,
+        min_message_level,
         terminal_dimensions,
         initial_delay,
         frames_per_second,
         keep_running_if_progress_is_empty,
         hide_cursor,
         throughput,
+        max_idle_redraw_interval,
     } = config;
     let config = draw::Options {
         output_is_terminal,
@@ -155,6 +175,7 @@ pub fn render(
         timestamp,
         keep_running_if_progress_is_empty,
         level_filter,
+        min_message_level,
         hide_cursor,
     };
 
@@ -188,16 +209,21 @@ pub fn render(
                 std::thread::sleep(Duration::from_secs_f32(secs));
             });
 
+            let mut last_frame = None;
+            let mut last_show_progress = false;
             for event in event_recv {
                 match event {
                     Event::Tick => {
-                        draw::all(
-                            &mut out,
-                            &progress,
-                            SHOW_PROGRESS.load(Ordering::Relaxed),
-                            &mut state,
-                            &config,
-                        )?;
+                        let show_progress = SHOW_PROGRESS.load(Ordering::Relaxed);
+                        let generation = progress.generation();
+                        if show_progress == last_show_progress
+                            && is_idle(last_frame, generation, max_idle_redraw_interval)
+                        {
+                            continue;
+                        }
+                        draw::all(&mut out, &progress, show_progress, &mut state, &config)?;
+                        last_frame = Some((Instant::now(), generation));
+                        last_show_progress = show_progress;
                     }
                     Event::Quit => break,
                 }
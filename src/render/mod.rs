@@ -7,3 +7,24 @@ pub use self::tui::render as tui;
 pub mod line;
 #[cfg(feature = "render-line")]
 pub use self::line::render as line;
+
+#[cfg(feature = "render-json")]
+pub mod json;
+#[cfg(feature = "render-json")]
+pub use self::json::render as json;
+
+/// Returns true if the frame drawn at the given time and `generation` is still current, as `generation` didn't change
+/// and the frame isn't older than `max_idle_interval`.
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+fn is_idle(
+    last_frame: Option<(std::time::Instant, Option<u64>)>,
+    generation: Option<u64>,
+    max_idle_interval: Option<std::time::Duration>,
+) -> bool {
+    match (last_frame, generation, max_idle_interval) {
+        (Some((drawn_at, Some(last_generation))), Some(generation), Some(max_idle_interval)) => {
+            last_generation == generation && drawn_at.elapsed() < max_idle_interval
+        }
+        _ => false,
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     messages::Message,
-    progress::{Key, Task},
+    progress::{self, Key, Task},
     render::tui::{
         draw,
         utils::{block_width, rect},
@@ -32,6 +32,12 @@ pub struct State {
     pub last_tree_column_width: Option<u16>,
     pub next_tree_column_width: Option<u16>,
     pub throughput: Option<Throughput>,
+    /// The task selected by the user, if any
+    pub selected: Option<Key>,
+    /// If true, details of the selected task are shown in place of the information pane
+    pub show_details: bool,
+    /// If true, only messages of the selected task and its subtree are shown
+    pub filter_messages: bool,
 }
 
 pub(crate) fn all(
@@ -42,9 +48,22 @@ pub(crate) fn all(
     bound: Rect,
     buf: &mut Buffer,
 ) {
+    let details = if state.show_details {
+        state
+            .selected
+            .as_ref()
+            .and_then(|selected| entries.binary_search_by(|e| e.0.cmp(selected)).ok())
+            .map(|index| task_details(&entries[index].1))
+    } else {
+        None
+    };
     let (bound, info_pane) = compute_info_bound(
         bound,
-        if state.hide_info { &[] } else { &state.information },
+        if state.hide_info {
+            &[]
+        } else {
+            details.as_deref().unwrap_or(&state.information)
+        },
         state.maximize_info,
     );
     let bold = Style::default().add_modifier(Modifier::BOLD);
@@ -89,13 +108,57 @@ pub(crate) fn all(
                 ..rect::line_bound(bound, bound.height.saturating_sub(1) as usize)
             },
             &mut state.message_offset,
+            state.filter_messages && state.selected.is_some(),
             buf,
         );
     }
 
     if let Some(info_pane) = info_pane {
-        draw::information::pane(&state.information, info_pane, buf);
+        draw::information::pane(details.as_deref().unwrap_or(&state.information), info_pane, buf);
+    }
+}
+
+fn task_details(task: &Task) -> Vec<Line> {
+    let mut lines = vec![Line::Title(task.name.clone())];
+    match task.progress.as_ref() {
+        Some(progress) => {
+            lines.push(Line::Text(format!(
+                "state: {}",
+                match progress.state {
+                    progress::State::Blocked(reason, _) => format!("blocked ({})", reason),
+                    progress::State::Halted(reason, _) => format!("halted ({})", reason),
+                    progress::State::Running => "running".into(),
+                    progress::State::Finished(_) => "finished".into(),
+                    progress::State::Failed(_) => "failed".into(),
+                    progress::State::Cancelled(_) => "cancelled".into(),
+                }
+            )));
+            lines.push(Line::Text(format!(
+                "progress: {}",
+                match (progress.unit.as_ref(), progress.done_at) {
+                    (Some(unit), done_at) => unit.display(progress.step, done_at, None).to_string(),
+                    (None, Some(done_at)) => format!("{}/{}", progress.step, done_at),
+                    (None, None) => progress.step.to_string(),
+                }
+            )));
+            if let Some(elapsed) = progress.elapsed() {
+                lines.push(Line::Text(format!(
+                    "elapsed: {}",
+                    humantime::format_duration(Duration::from_secs(elapsed.as_secs()))
+                )));
+            }
+        }
+        None => lines.push(Line::Text("group".into())),
+    }
+    if !task.attributes.is_empty() {
+        lines.push(Line::Title("Attributes".into()));
+        lines.extend(
+            task.attributes
+                .iter()
+                .map(|(key, value)| Line::Text(format!("{}: {}", key, value))),
+        );
     }
+    lines
 }
 
 fn compute_pane_bounds(messages: &[Message], inner: Rect, messages_fullscreen: bool) -> (Rect, Option<Rect>) {
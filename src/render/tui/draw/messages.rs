@@ -13,14 +13,28 @@ use tui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mut u16, buf: &mut Buffer) {
+pub fn pane(
+    messages: &[Message],
+    bound: Rect,
+    overflow_bound: Rect,
+    offset: &mut u16,
+    filtered: bool,
+    buf: &mut Buffer,
+) {
     let bold = Style::default().add_modifier(Modifier::BOLD);
     let block = Block::default()
-        .title(Span::styled("Messages", bold))
+        .title(Span::styled(
+            if filtered {
+                "Messages of selected task"
+            } else {
+                "Messages"
+            },
+            bold,
+        ))
         .borders(Borders::TOP);
     let inner_bound = block.inner(bound);
     block.render(bound, buf);
-    let help_text = " ⨯ = `| ▢ = ~ ";
+    let help_text = " ⨯ = `| ▢ = ~ | ⧩ = f ";
     draw_text_with_ellipsis_nowrap(rect::snap_to_right(bound, block_width(help_text)), buf, help_text, bold);
 
     let bound = inner_bound;
@@ -38,6 +52,7 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             message,
             level,
             origin,
+            ..
         },
     ) in messages
         .iter()
@@ -94,7 +109,9 @@ const LEVEL_TEXT_WIDTH: u16 = 4;
 fn format_level_column(level: MessageLevel) -> &'static str {
     use MessageLevel::*;
     match level {
+        Debug => "dbug",
         Info => "info",
+        Warning => "warn",
         Failure => "fail",
         Success => "done",
     }
@@ -106,7 +123,9 @@ fn level_to_style(level: MessageLevel) -> Style {
         .fg(Color::Black)
         .add_modifier(Modifier::BOLD)
         .bg(match level {
+            Debug => Color::Blue,
             Info => Color::White,
+            Warning => Color::Yellow,
             Failure => Color::Red,
             Success => Color::Green,
         })
@@ -24,6 +24,8 @@ use tui::{
 use tui_react::fill_background;
 
 const MIN_TREE_WIDTH: u16 = 20;
+/// The color of progress bars of tasks lingering past their `Item`
+const LINGERING_COLOR: Color = Color::DarkGray;
 
 pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
@@ -35,6 +37,18 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             false
         };
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
+    if let Some(selected_index) = state
+        .selected
+        .as_ref()
+        .and_then(|selected| entries.binary_search_by(|e| e.0.cmp(selected)).ok())
+    {
+        let selected_line = selected_index as u16;
+        if selected_line < state.task_offset {
+            state.task_offset = selected_line;
+        } else if bound.height > 0 && selected_line >= state.task_offset + bound.height {
+            state.task_offset = selected_line + 1 - bound.height;
+        }
+    }
 
     if entries.is_empty() {
         return;
@@ -48,7 +62,7 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
                 width: desired_max_tree_draw_width,
                 ..bound
             };
-            let computed = draw_tree(entries, buf, tree_bound, state.task_offset);
+            let computed = draw_tree(entries, buf, tree_bound, state.task_offset, state.selected.as_ref());
             state.last_tree_column_width = Some(computed);
         } else {
             state.last_tree_column_width = Some(0);
@@ -98,19 +112,22 @@ pub(crate) fn headline(
     buf: &mut Buffer,
     bound: Rect,
 ) {
-    let (num_running_tasks, num_blocked_tasks, num_groups) = entries.iter().fold(
-        (0, 0, 0),
-        |(mut running, mut blocked, mut groups), (_key, Task { progress, .. })| {
+    let (num_running_tasks, num_blocked_tasks, num_finished_tasks, num_failed_tasks, num_groups) = entries.iter().fold(
+        (0, 0, 0, 0, 0),
+        |(mut running, mut blocked, mut finished, mut failed, mut groups), (_key, Task { progress, .. })| {
             match progress.as_ref().map(|p| p.state) {
                 Some(progress::State::Running) => running += 1,
                 Some(progress::State::Blocked(_, _)) | Some(progress::State::Halted(_, _)) => blocked += 1,
+                Some(progress::State::Finished(_)) => finished += 1,
+                // cancelled tasks didn't succeed either
+                Some(progress::State::Failed(_)) | Some(progress::State::Cancelled(_)) => failed += 1,
                 None => groups += 1,
             }
-            (running, blocked, groups)
+            (running, blocked, finished, failed, groups)
         },
     );
     let text = format!(
-        " {} {} {:3} running + {:3} blocked + {:3} groups = {} ",
+        " {} {} {:3} running + {:3} blocked + {:3} finished + {:3} failed + {:3} groups = {} ",
         match interrupt_mode {
             InterruptDrawInfo::Instantly => "'q' or CTRL+c to quit",
             InterruptDrawInfo::Deferred(interrupt_requested) => {
@@ -132,6 +149,8 @@ pub(crate) fn headline(
         },
         num_running_tasks,
         num_blocked_tasks,
+        num_finished_tasks,
+        num_failed_tasks,
         num_groups,
         entries.len()
     );
@@ -191,7 +210,21 @@ pub fn draw_progress(
             None => state,
         });
 
-    for (line, (entry_index, (key, Task { progress, name: title }))) in entries
+    for (
+        line,
+        (
+            entry_index,
+            (
+                key,
+                Task {
+                    progress,
+                    name: title,
+                    dropped_at,
+                    ..
+                },
+            ),
+        ),
+    ) in entries
         .iter()
         .enumerate()
         .skip(offset as usize)
@@ -220,11 +253,18 @@ pub fn draw_progress(
         let tree_prefix = level_prefix(entries, entry_index);
         let progress_rect = rect::offset_x(line_bound, block_width(&tree_prefix) as u16);
         draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, None);
-        match progress.as_ref().map(|p| (p.fraction(), p.state, p.step)) {
-            Some((Some(fraction), state, _step)) => {
+        let lingering = dropped_at.is_some();
+        let text_style = if lingering {
+            Style::default().add_modifier(Modifier::DIM)
+        } else {
+            Style::default()
+        };
+        match progress.as_ref().map(|p| (p, p.fraction(), p.state, p.step)) {
+            Some((value, Some(fraction), state, _step)) => {
                 let mut progress_text = progress_text;
-                add_block_eta(state, &mut progress_text);
+                add_block_eta(value, throughput, &mut progress_text);
                 let (bound, style) = draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| match state {
+                    _ if lingering => LINGERING_COLOR,
                     progress::State::Blocked(_, _) => Color::Red,
                     progress::State::Halted(_, _) => Color::LightRed,
                     progress::State::Running => {
@@ -234,20 +274,23 @@ pub fn draw_progress(
                             Color::Yellow
                         }
                     }
+                    progress::State::Finished(_) => Color::Green,
+                    progress::State::Failed(_) => Color::Red,
+                    progress::State::Cancelled(_) => Color::Gray,
                 });
                 let style_fn = move |_t: &str, x: u16, _y: u16| {
                     if x < bound.right() {
                         style
                     } else {
-                        Style::default()
+                        text_style
                     }
                 };
                 draw_text_nowrap_fn(progress_rect, buf, progress_text, style_fn);
             }
-            Some((None, state, step)) => {
+            Some((value, None, state, step)) => {
                 let mut progress_text = progress_text;
-                add_block_eta(state, &mut progress_text);
-                draw_text_with_ellipsis_nowrap(progress_rect, buf, progress_text, None);
+                add_block_eta(value, throughput, &mut progress_text);
+                draw_text_with_ellipsis_nowrap(progress_rect, buf, progress_text, text_style);
                 let bar_rect = rect::offset_x(line_bound, max_progress_label_width as u16);
                 draw_spinner(
                     buf,
@@ -255,22 +298,27 @@ pub fn draw_progress(
                     step,
                     line,
                     match state {
+                        _ if lingering => LINGERING_COLOR,
                         progress::State::Blocked(_, _) => Color::Red,
                         progress::State::Halted(_, _) => Color::LightRed,
                         progress::State::Running => Color::White,
+                        progress::State::Finished(_) => Color::Green,
+                        progress::State::Failed(_) => Color::Red,
+                        progress::State::Cancelled(_) => Color::Gray,
                     },
                 );
             }
             None => {
-                let bold = Style::default().add_modifier(Modifier::BOLD);
-                draw_text_nowrap_fn(progress_rect, buf, progress_text, |_, _, _| Style::default());
+                let bold = text_style.add_modifier(Modifier::BOLD);
+                draw_text_nowrap_fn(progress_rect, buf, progress_text, |_, _, _| text_style);
                 draw_text_with_ellipsis_nowrap(progress_rect, buf, format!(" {} ", title), bold);
             }
         }
     }
 }
 
-fn add_block_eta(state: progress::State, progress_text: &mut String) {
+fn add_block_eta(value: &Value, throughput: Option<unit::display::Throughput>, progress_text: &mut String) {
+    let state = value.state;
     match state {
         progress::State::Blocked(reason, maybe_eta) | progress::State::Halted(reason, maybe_eta) => {
             progress_text.push_str(" [");
@@ -291,7 +339,37 @@ fn add_block_eta(state: progress::State, progress_text: &mut String) {
                 }
             }
         }
-        progress::State::Running => {}
+        progress::State::Finished(_) | progress::State::Failed(_) | progress::State::Cancelled(_) => {
+            progress_text.push_str(match state {
+                progress::State::Finished(_) => " [finished",
+                progress::State::Failed(_) => " [failed",
+                _ => " [cancelled",
+            });
+            if let Some(elapsed) = value.elapsed() {
+                progress_text.push_str(&format!(
+                    " after {}",
+                    format_duration(Duration::from_secs(elapsed.as_secs()))
+                ));
+            }
+            progress_text.push(']');
+        }
+        progress::State::Running => {
+            if let Some(elapsed) = value.elapsed() {
+                progress_text.push_str(&format!(
+                    " [{}]",
+                    format_duration(Duration::from_secs(elapsed.as_secs()))
+                ));
+            }
+            let eta = value
+                .eta(throughput)
+                .filter(|_| !matches!(&value.unit, Some(unit) if unit.shows_eta()));
+            if let Some(eta) = eta {
+                progress_text.push_str(&format!(
+                    " → {} left",
+                    format_duration(Duration::from_secs(eta.as_secs()))
+                ));
+            }
+        }
     }
 }
 
@@ -351,7 +429,7 @@ fn draw_progress_bar_fn(
     (fractional_progress_rect, Style::default().bg(color).fg(Color::Black))
 }
 
-pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset: u16) -> u16 {
+pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset: u16, selected: Option<&Key>) -> u16 {
     let mut max_prefix_len = 0;
     for (line, (entry_index, entry)) in entries
         .iter()
@@ -366,10 +444,16 @@ pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset:
         let tree_prefix = format!("{} {} ", level_prefix(entries, entry_index), entry.1.name);
         max_prefix_len = max_prefix_len.max(block_width(&tree_prefix));
 
-        let style = if entry.1.progress.is_none() {
-            Style::default().add_modifier(Modifier::BOLD).into()
+        let style = match (entry.1.progress.is_none(), entry.1.dropped_at.is_some()) {
+            (true, true) => Style::default().add_modifier(Modifier::BOLD | Modifier::DIM).into(),
+            (true, false) => Style::default().add_modifier(Modifier::BOLD).into(),
+            (false, true) => Style::default().add_modifier(Modifier::DIM).into(),
+            (false, false) => None,
+        };
+        let style = if selected == Some(&entry.0) {
+            Some(style.unwrap_or_default().add_modifier(Modifier::REVERSED))
         } else {
-            None
+            style
         };
         draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, style);
     }
@@ -378,10 +462,10 @@ pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset:
 
 fn level_prefix(entries: &[(Key, Task)], entry_index: usize) -> String {
     let adj = Key::adjacency(entries, entry_index);
-    let key = entries[entry_index].0;
+    let key = &entries[entry_index].0;
     let key_level = key.level();
     let is_orphan = adj.level() != key_level;
-    let mut buf = String::with_capacity(key_level as usize);
+    let mut buf = String::with_capacity(key_level);
     for level in 1..=key_level {
         use crate::progress::key::SiblingLocation::*;
         let is_child_level = level == key_level;
@@ -1,9 +1,14 @@
-use crate::{render::tui::draw, render::tui::ticker, Root, Throughput};
+use crate::{
+    messages::MessageLevel,
+    progress,
+    render::{is_idle, tui::draw, tui::ticker},
+    Root, Throughput,
+};
 
 use futures_lite::StreamExt;
 use std::{
     io::{self, Write},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tui::layout::Rect;
 
@@ -45,6 +50,17 @@ pub struct Options {
     /// Please note that you should add at least one item to the `prodash::Tree` before launching the application or else
     /// risk a race causing the TUI to sometimes not come up at all.
     pub stop_if_empty_progress: bool,
+
+    /// If set, _(default: None)_, only messages of the given level or a more severe one are shown.
+    ///
+    /// This is useful to hide `Debug` messages, for example.
+    pub min_message_level: Option<MessageLevel>,
+
+    /// If set, _(default: 1s)_, frames are skipped while the [generation](../../trait.Root.html#method.generation)
+    /// of the progress tree doesn't change, but only for up to the given duration to keep durations and throughput current.
+    ///
+    /// If unset, a frame is drawn on every tick. User input always causes a frame to be drawn.
+    pub max_idle_redraw_interval: Option<Duration>,
 }
 
 impl Default for Options {
@@ -56,6 +72,8 @@ impl Default for Options {
             recompute_column_width_every_nth_frame: None,
             window_size: None,
             stop_if_empty_progress: false,
+            min_message_level: None,
+            max_idle_redraw_interval: Some(Duration::from_secs(1)),
         }
     }
 }
@@ -140,6 +158,8 @@ pub fn render_with_input(
         recompute_column_width_every_nth_frame,
         throughput,
         stop_if_empty_progress,
+        min_message_level,
+        max_idle_redraw_interval,
     } = options;
     let mut terminal = new_terminal(AlternateRawScreen::try_from(out)?)?;
     terminal.hide_cursor()?;
@@ -165,11 +185,14 @@ pub fn render_with_input(
             .or(events);
 
         let mut tick = 0usize;
+        let mut last_frame: Option<(Instant, Option<u64>)> = None;
         let store_task_size_every = recompute_column_width_every_nth_frame.unwrap_or(1).max(1);
         while let Some(event) = events.next().await {
             let mut skip_redraw = false;
             match event {
-                Event::Tick => {}
+                Event::Tick => {
+                    skip_redraw = is_idle(last_frame, progress.generation(), max_idle_redraw_interval);
+                }
                 Event::Input(key) => match key {
                     Key::Esc | Key::Char('q') | Key::Ctrl('c') | Key::Ctrl('[') => match interrupt_mode {
                         InterruptDrawInfo::Instantly => break,
@@ -187,6 +210,14 @@ pub fn render_with_input(
                     Key::Char('u') => state.task_offset = state.task_offset.saturating_sub(10),
                     Key::Char('[') => state.hide_info = !state.hide_info,
                     Key::Char('{') => state.maximize_info = !state.maximize_info,
+                    Key::Down => select_relative(&mut state.selected, &entries, Direction::Next),
+                    Key::Up => select_relative(&mut state.selected, &entries, Direction::Previous),
+                    Key::Char('\n') => state.show_details = !state.show_details,
+                    Key::Char('f') => state.filter_messages = !state.filter_messages,
+                    Key::Char('x') => match state.selected.as_ref() {
+                        Some(selected) => progress.cancel(selected),
+                        None => skip_redraw = true,
+                    },
                     _ => skip_redraw = true,
                 },
                 Event::SetWindowSize(bound) => state.user_provided_window_size = Some(bound),
@@ -209,6 +240,7 @@ pub fn render_with_input(
             }
             if !skip_redraw {
                 tick += 1;
+                last_frame = Some((Instant::now(), progress.generation()));
 
                 progress.sorted_snapshot(&mut entries);
                 if stop_if_empty_progress && entries.is_empty() {
@@ -221,7 +253,13 @@ pub fn render_with_input(
                     .unwrap_or(terminal_window_size);
                 let buf = terminal.current_buffer_mut();
                 if !state.hide_messages {
-                    progress.copy_messages(&mut messages);
+                    match state.selected.as_ref().filter(|_| state.filter_messages) {
+                        Some(selected) => progress.copy_messages_from(selected, true, &mut messages),
+                        None => progress.copy_messages(&mut messages),
+                    }
+                    if let Some(min_level) = min_message_level {
+                        messages.retain(|m| m.level >= min_level);
+                    }
                 }
 
                 draw::all(&mut state, interrupt_mode, &entries, &messages, window_size, buf);
@@ -238,6 +276,43 @@ pub fn render_with_input(
     Ok(render_fut)
 }
 
+enum Direction {
+    Next,
+    Previous,
+}
+
+/// Move the `selected` task in the given `direction` within `entries`, selecting the first or last one if there
+/// is no selection yet.
+fn select_relative(
+    selected: &mut Option<progress::Key>,
+    entries: &[(progress::Key, progress::Task)],
+    direction: Direction,
+) {
+    if entries.is_empty() {
+        *selected = None;
+        return;
+    }
+    let index = match selected
+        .as_ref()
+        .map(|selected| entries.binary_search_by(|e| e.0.cmp(selected)))
+    {
+        None => match direction {
+            Direction::Next => 0,
+            Direction::Previous => entries.len() - 1,
+        },
+        Some(Ok(index)) => match direction {
+            Direction::Next => (index + 1).min(entries.len() - 1),
+            Direction::Previous => index.saturating_sub(1),
+        },
+        // the selected task is gone, pick its closest neighbour
+        Some(Err(index)) => match direction {
+            Direction::Next => index.min(entries.len() - 1),
+            Direction::Previous => index.saturating_sub(1),
+        },
+    };
+    *selected = Some(entries[index].0.clone());
+}
+
 /// An easy-to-use version of `render_with_input(…)` that does not allow state manipulation via an event stream.
 pub fn render(
     out: impl std::io::Write,
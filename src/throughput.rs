@@ -90,12 +90,12 @@ impl Throughput {
     ) -> Option<unit::display::Throughput> {
         progress.and_then(|progress| {
             self.elapsed
-                .and_then(|elapsed| match self.sorted_by_key.binary_search_by_key(key, |t| t.0) {
+                .and_then(|elapsed| match self.sorted_by_key.binary_search_by(|t| t.0.cmp(key)) {
                     Ok(index) => self.sorted_by_key[index].1.update(progress.step, elapsed),
                     Err(index) => {
                         let state = State::new(progress.step, elapsed);
                         let tp = state.throughput();
-                        self.sorted_by_key.insert(index, (*key, state));
+                        self.sorted_by_key.insert(index, (key.clone(), state));
                         tp
                     }
                 })
@@ -103,6 +103,6 @@ impl Throughput {
     }
     pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
         self.sorted_by_key
-            .retain(|(key, _)| sorted_values.binary_search_by_key(key, |e| e.0).is_ok());
+            .retain(|(key, _)| sorted_values.binary_search_by(|e| e.0.cmp(key)).is_ok());
     }
 }
@@ -79,6 +79,37 @@ pub trait Progress: Send + 'static {
     fn fail(&mut self, message: impl Into<String>) {
         self.message(MessageLevel::Failure, message)
     }
+    /// Create a message providing detailed information for debugging the task
+    fn debug(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Debug, message)
+    }
+    /// Create a message indicating a recoverable problem, like a retry or a fallback
+    fn warn(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Warning, message)
+    }
+    /// Set the attribute with the given `key` to `value`, to provide structured information about the task.
+    ///
+    /// The default implementation discards it, as not all implementations keep attributes.
+    fn set_attribute(&mut self, _key: impl Into<String>, _value: impl Into<String>) {}
+    /// Returns true if cancellation of the task was requested, in which case it should stop as soon as possible.
+    ///
+    /// The default implementation returns false, as not all implementations support cancellation.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+    /// Indicate that the task finished successfully.
+    ///
+    /// The default implementation does nothing, as not all implementations keep track of the task's state.
+    fn finished(&mut self) {}
+    /// Indicate that the task failed.
+    ///
+    /// The default implementation does nothing, as not all implementations keep track of the task's state.
+    fn failed(&mut self) {}
+    /// Indicate that the task was cancelled before it could finish.
+    ///
+    /// The default implementation does nothing, as not all implementations keep track of the task's state.
+    fn cancelled(&mut self) {}
+
     /// A shorthand to print throughput information
     fn show_throughput(&mut self, start: Instant) {
         let step = self.step();
@@ -124,6 +155,171 @@ pub trait Progress: Send + 'static {
     }
 }
 
+/// An object-safe version of [`Progress`](./trait.Progress.html), to be used as `Box<dyn DynProgress>` where generics aren't desired.
+///
+/// It's implemented for every `Progress`, and `Box<dyn DynProgress>` implements `Progress` in turn,
+/// so both can be used interchangeably. See the equally named methods of `Progress` for documentation.
+///
+/// **Note** that there is usually no need to import this trait, as doing so alongside `Progress` makes method calls ambiguous.
+pub trait DynProgress: Send + 'static {
+    /// See [`Progress::add_child(…)`](./trait.Progress.html#tymethod.add_child).
+    fn add_child(&mut self, name: String) -> Box<dyn DynProgress>;
+    /// See [`Progress::init(…)`](./trait.Progress.html#tymethod.init).
+    fn init(&mut self, max: Option<progress::Step>, unit: Option<Unit>);
+    /// See [`Progress::set(…)`](./trait.Progress.html#tymethod.set).
+    fn set(&mut self, step: progress::Step);
+    /// See [`Progress::unit()`](./trait.Progress.html#method.unit).
+    fn unit(&self) -> Option<Unit>;
+    /// See [`Progress::max()`](./trait.Progress.html#method.max).
+    fn max(&self) -> Option<progress::Step>;
+    /// See [`Progress::step()`](./trait.Progress.html#tymethod.step).
+    fn step(&self) -> progress::Step;
+    /// See [`Progress::inc_by(…)`](./trait.Progress.html#tymethod.inc_by).
+    fn inc_by(&mut self, step: progress::Step);
+    /// See [`Progress::set_name(…)`](./trait.Progress.html#tymethod.set_name).
+    fn set_name(&mut self, name: String);
+    /// See [`Progress::name()`](./trait.Progress.html#tymethod.name).
+    fn name(&self) -> Option<String>;
+    /// See [`Progress::message(…)`](./trait.Progress.html#tymethod.message).
+    fn message(&mut self, level: MessageLevel, message: String);
+    /// See [`Progress::set_attribute(…)`](./trait.Progress.html#method.set_attribute).
+    fn set_attribute(&mut self, key: String, value: String);
+    /// See [`Progress::is_cancelled()`](./trait.Progress.html#method.is_cancelled).
+    fn is_cancelled(&self) -> bool;
+    /// See [`Progress::finished()`](./trait.Progress.html#method.finished).
+    fn finished(&mut self);
+    /// See [`Progress::failed()`](./trait.Progress.html#method.failed).
+    fn failed(&mut self);
+    /// See [`Progress::cancelled()`](./trait.Progress.html#method.cancelled).
+    fn cancelled(&mut self);
+}
+
+impl<T: Progress> DynProgress for T {
+    fn add_child(&mut self, name: String) -> Box<dyn DynProgress> {
+        Box::new(Progress::add_child(self, name))
+    }
+
+    fn init(&mut self, max: Option<progress::Step>, unit: Option<Unit>) {
+        Progress::init(self, max, unit)
+    }
+
+    fn set(&mut self, step: progress::Step) {
+        Progress::set(self, step)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        Progress::unit(self)
+    }
+
+    fn max(&self) -> Option<progress::Step> {
+        Progress::max(self)
+    }
+
+    fn step(&self) -> progress::Step {
+        Progress::step(self)
+    }
+
+    fn inc_by(&mut self, step: progress::Step) {
+        Progress::inc_by(self, step)
+    }
+
+    fn set_name(&mut self, name: String) {
+        Progress::set_name(self, name)
+    }
+
+    fn name(&self) -> Option<String> {
+        Progress::name(self)
+    }
+
+    fn message(&mut self, level: MessageLevel, message: String) {
+        Progress::message(self, level, message)
+    }
+
+    fn set_attribute(&mut self, key: String, value: String) {
+        Progress::set_attribute(self, key, value)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        Progress::is_cancelled(self)
+    }
+
+    fn finished(&mut self) {
+        Progress::finished(self)
+    }
+
+    fn failed(&mut self) {
+        Progress::failed(self)
+    }
+
+    fn cancelled(&mut self) {
+        Progress::cancelled(self)
+    }
+}
+
+impl Progress for Box<dyn DynProgress> {
+    type SubProgress = Box<dyn DynProgress>;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        DynProgress::add_child(&mut **self, name.into())
+    }
+
+    fn init(&mut self, max: Option<progress::Step>, unit: Option<Unit>) {
+        DynProgress::init(&mut **self, max, unit)
+    }
+
+    fn set(&mut self, step: progress::Step) {
+        DynProgress::set(&mut **self, step)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        DynProgress::unit(&**self)
+    }
+
+    fn max(&self) -> Option<progress::Step> {
+        DynProgress::max(&**self)
+    }
+
+    fn step(&self) -> progress::Step {
+        DynProgress::step(&**self)
+    }
+
+    fn inc_by(&mut self, step: progress::Step) {
+        DynProgress::inc_by(&mut **self, step)
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        DynProgress::set_name(&mut **self, name.into())
+    }
+
+    fn name(&self) -> Option<String> {
+        DynProgress::name(&**self)
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        DynProgress::message(&mut **self, level, message.into())
+    }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        DynProgress::set_attribute(&mut **self, key.into(), value.into())
+    }
+
+    fn is_cancelled(&self) -> bool {
+        DynProgress::is_cancelled(&**self)
+    }
+
+    fn finished(&mut self) {
+        DynProgress::finished(&mut **self)
+    }
+
+    fn failed(&mut self) {
+        DynProgress::failed(&mut **self)
+    }
+
+    fn cancelled(&mut self) {
+        DynProgress::cancelled(&mut **self)
+    }
+}
+
 use crate::messages::{Message, MessageCopyState};
 
 /// The top level of a progress task hiearchy, with `progress::Task`s identified with `progress::Key`s
@@ -147,4 +343,24 @@ pub trait Root {
     /// Copy only new messages from the internal ring buffer into the given `out`
     /// vector. Messages are ordered from oldest to newest.
     fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState;
+
+    /// Copy all messages sent by the task with the given `key`, or by any task underneath it if `include_subtree` is true,
+    /// into the given `out` vector. Messages are ordered from oldest to newest.
+    fn copy_messages_from(&self, key: &progress::Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.copy_messages(out);
+        out.retain(|m| m.is_from(key, include_subtree));
+    }
+
+    /// Request cancellation of the task with the given `key` and all tasks underneath it.
+    ///
+    /// The default implementation does nothing, as not all implementations support cancellation.
+    fn cancel(&self, _key: &progress::Key) {}
+
+    /// Returns a number which changes whenever tasks or messages change, or `None` if this isn't tracked.
+    ///
+    /// Renderers use it to skip redrawing if nothing changed since the last frame.
+    /// The default implementation returns `None`, causing renderers to redraw on each tick.
+    fn generation(&self) -> Option<u64> {
+        None
+    }
 }
@@ -5,7 +5,130 @@ use crate::{
 };
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::{ops::Deref, sync::Arc, time::SystemTime};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+/// The target of all log records of messages sent to the tree, which is why they are not captured by [`LogCapture`](./struct.LogCapture.html).
+#[cfg(feature = "progress-tree-log")]
+pub(crate) const LOG_TARGET: &str = module_path!();
+
+/// The part of a task's progress that changes most frequently, shared between an `Item` and its tree
+/// so that it can be altered without locking.
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    /// The amount of progress made so far
+    pub(crate) step: AtomicUsize,
+    /// If true, the task is blocked, halted or done, and the state stored in the tree applies.
+    /// Otherwise the task is running.
+    pub(crate) not_running: AtomicBool,
+    /// The time at which progress was last made, in nanoseconds since the unix epoch, or 0 if there was none yet.
+    pub(crate) last_advanced_nanos: AtomicU64,
+    /// If true, cancellation of the task was requested, and it should stop as soon as possible.
+    pub(crate) cancellation_requested: AtomicBool,
+    /// If true, the task changed since the tree's generation was last computed.
+    ///
+    /// It's kept per task so that making progress doesn't write to memory shared by all tasks.
+    pub(crate) dirty: AtomicBool,
+}
+
+impl Shared {
+    fn deep_clone(&self) -> Shared {
+        Shared {
+            step: AtomicUsize::new(self.step.load(Ordering::Relaxed)),
+            not_running: AtomicBool::new(self.not_running.load(Ordering::Relaxed)),
+            last_advanced_nanos: AtomicU64::new(self.last_advanced_nanos.load(Ordering::Relaxed)),
+            cancellation_requested: AtomicBool::new(self.cancellation_requested.load(Ordering::Relaxed)),
+            dirty: AtomicBool::new(self.dirty.load(Ordering::Relaxed)),
+        }
+    }
+
+    pub(crate) fn changed(&self) {
+        if !self.dirty.load(Ordering::Relaxed) {
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    /// Returns true if the task changed since the last call, resetting the flag.
+    pub(crate) fn take_changed(&self) -> bool {
+        self.dirty.load(Ordering::Relaxed) && self.dirty.swap(false, Ordering::AcqRel)
+    }
+
+    fn advanced_now(&self) {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        self.last_advanced_nanos.store(nanos, Ordering::Relaxed);
+    }
+
+    fn last_advanced_at(&self) -> Option<SystemTime> {
+        match self.last_advanced_nanos.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos)),
+        }
+    }
+}
+
+/// A task as stored in the tree, along with the lock-free portion of its progress.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub(crate) task: Task,
+    pub(crate) shared: Arc<Shared>,
+    /// If set, the `Item` was dropped and the task will be removed from the tree at the given time.
+    pub(crate) lingers_until: Option<Instant>,
+    /// If true, the task's progress is derived from its children whenever a snapshot is taken.
+    pub(crate) aggregate_children: bool,
+    /// The weight with which the task contributes to the progress of an aggregating parent, if set.
+    pub(crate) weight: Option<Step>,
+}
+
+impl Entry {
+    pub(crate) fn new(name: String) -> Self {
+        Entry {
+            task: Task {
+                name,
+                ..Default::default()
+            },
+            shared: Default::default(),
+            lingers_until: None,
+            aggregate_children: false,
+            weight: None,
+        }
+    }
+
+    /// Returns true if the task is lingering past its `Item` and should now be removed.
+    pub(crate) fn is_expired(&self, now: Instant) -> bool {
+        matches!(self.lingers_until, Some(until) if until <= now)
+    }
+
+    /// Return a copy of the task with all progress information being up to date.
+    pub(crate) fn snapshot(&self) -> Task {
+        let mut task = self.task.clone();
+        if let Some(progress) = task.progress.as_mut() {
+            progress.step = self.shared.step.load(Ordering::Relaxed);
+            progress.last_advanced_at = self.shared.last_advanced_at();
+            if !self.shared.not_running.load(Ordering::Relaxed) {
+                progress.state = State::Running;
+            }
+        }
+        task
+    }
+
+    fn deep_clone(&self) -> Entry {
+        Entry {
+            task: self.task.clone(),
+            shared: Arc::new(self.shared.deep_clone()),
+            lingers_until: self.lingers_until,
+            aggregate_children: self.aggregate_children,
+            weight: self.weight,
+        }
+    }
+}
 
 /// A `Tree` represents an element of the progress tree.
 ///
@@ -28,13 +151,36 @@ use std::{ops::Deref, sync::Arc, time::SystemTime};
 pub struct Item {
     pub(crate) key: Key,
     pub(crate) highest_child_id: key::Id,
-    pub(crate) tree: Arc<DashMap<Key, Task>>,
+    pub(crate) shared: Arc<Shared>,
+    pub(crate) tree: Arc<DashMap<Key, Entry>>,
     pub(crate) messages: Arc<Mutex<MessageRingBuffer>>,
+    pub(crate) linger: Option<Duration>,
+    /// The amount of changes to the structure of the tree and to its messages, shared by all of its items.
+    ///
+    /// Changes to individual tasks are tracked by the tasks themselves.
+    pub(crate) changes: Arc<AtomicU64>,
 }
 
 impl Drop for Item {
     fn drop(&mut self) {
-        self.tree.remove(&self.key);
+        match self.linger {
+            Some(linger) => {
+                if let Some(mut r) = self.tree.get_mut(&self.key) {
+                    let entry = r.value_mut();
+                    entry.task.dropped_at = Some(SystemTime::now());
+                    entry.lingers_until = Some(Instant::now() + linger);
+                    entry.shared.changed();
+                }
+                // Without this, lingering tasks would only ever be removed when taking snapshots.
+                self.remove_expired();
+            }
+            None => {
+                // This must happen after the removal, so a renderer seeing the new generation also sees the task removed.
+                if self.tree.remove(&self.key).is_some() {
+                    self.changes.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
     }
 }
 
@@ -54,83 +200,107 @@ impl Item {
     /// **Note** that this method can be called multiple times, changing the bounded-ness and unit at will.
     pub fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
         if let Some(mut r) = self.tree.get_mut(&self.key) {
-            r.value_mut().progress = Some(Value {
+            self.shared.step.store(0, Ordering::Relaxed);
+            self.shared.not_running.store(false, Ordering::Relaxed);
+            self.shared.last_advanced_nanos.store(0, Ordering::Relaxed);
+            r.value_mut().task.progress = Some(Value {
                 done_at: max,
                 unit,
+                initialized_at: Some(SystemTime::now()),
                 ..Default::default()
-            })
+            });
+            self.shared.changed();
         };
     }
 
-    fn alter_progress(&mut self, f: impl FnMut(&mut Value)) {
+    fn alter_progress(&self, f: impl FnMut(&mut Value)) {
         if let Some(mut r) = self.tree.get_mut(&self.key) {
-            // NOTE: since we wrap around, if there are more tasks than we can have IDs for,
-            // and if all these tasks are still alive, two progress trees may see the same ID
-            // when these go out of scope, they delete the key and the other tree will not find
-            // its value anymore. Besides, it's probably weird to see tasks changing their progress
-            // all the time…
-            r.value_mut().progress.as_mut().map(f);
+            r.value_mut().task.progress.as_mut().map(f);
+            self.shared.changed();
         };
     }
 
+    fn set_state(&self, state: State) {
+        let not_running = &self.shared.not_running;
+        self.alter_progress(|p| {
+            p.state = state;
+            not_running.store(true, Ordering::Relaxed);
+        });
+    }
+
+    /// Mark the task as running, undoing a previous call to `blocked(…)`, `halted(…)` or any other state change.
+    fn set_running(&self) {
+        if self.shared.not_running.load(Ordering::Relaxed) {
+            self.shared.not_running.store(false, Ordering::Relaxed);
+        }
+    }
+
     /// Set the name of this task's progress to the given `name`.
     pub fn set_name(&mut self, name: impl Into<String>) {
         if let Some(mut r) = self.tree.get_mut(&self.key) {
-            r.value_mut().name = name.into();
+            r.value_mut().task.name = name.into();
+            self.shared.changed();
         };
     }
 
     /// Get the name of this task's progress
     pub fn name(&self) -> Option<String> {
-        self.tree.get(&self.key).map(|r| r.value().name.to_owned())
+        self.tree.get(&self.key).map(|r| r.value().task.name.to_owned())
     }
 
     pub fn step(&self) -> Option<Step> {
-        self.tree
-            .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().map(|p| p.step))
+        self.tree.get(&self.key).and_then(|r| {
+            r.value()
+                .task
+                .progress
+                .as_ref()
+                .map(|_| self.shared.step.load(Ordering::Relaxed))
+        })
     }
 
     pub fn max(&self) -> Option<Step> {
         self.tree
             .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().and_then(|p| p.done_at))
+            .and_then(|r| r.value().task.progress.as_ref().and_then(|p| p.done_at))
     }
 
     pub fn unit(&self) -> Option<Unit> {
         self.tree
             .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().and_then(|p| p.unit.clone()))
+            .and_then(|r| r.value().task.progress.as_ref().and_then(|p| p.unit.clone()))
     }
 
     /// Set the current progress to the given `step`.
     ///
-    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// This call never locks, making it safe to use in hot loops.
+    ///
+    /// **Note**: that this call has no visible effect unless `init(…)` was called before.
     pub fn set(&mut self, step: Step) {
-        self.alter_progress(|p| {
-            p.step = step;
-            p.state = State::Running;
-        });
+        self.shared.step.store(step, Ordering::Relaxed);
+        self.shared.advanced_now();
+        self.shared.changed();
+        self.set_running();
     }
 
     /// Increment the current progress by the given `step`.
     ///
-    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// This call never locks, making it safe to use in hot loops.
+    ///
+    /// **Note**: that this call has no visible effect unless `init(…)` was called before.
     pub fn inc_by(&mut self, step: Step) {
-        self.alter_progress(|p| {
-            p.step += step;
-            p.state = State::Running;
-        });
+        self.shared.step.fetch_add(step, Ordering::Relaxed);
+        self.shared.advanced_now();
+        self.shared.changed();
+        self.set_running();
     }
 
     /// Increment the current progress by one.
     ///
-    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// This call never locks, making it safe to use in hot loops.
+    ///
+    /// **Note**: that this call has no visible effect unless `init(…)` was called before.
     pub fn inc(&mut self) {
-        self.alter_progress(|p| {
-            p.step += 1;
-            p.state = State::Running;
-        });
+        self.inc_by(1)
     }
 
     /// Call to indicate that progress cannot be indicated, and that the task cannot be interrupted.
@@ -142,7 +312,7 @@ impl Item {
     ///
     /// The blocked-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
     pub fn blocked(&mut self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Blocked(reason, eta));
+        self.set_state(State::Blocked(reason, eta));
     }
 
     /// Call to indicate that progress cannot be indicated, even though the task can be interrupted.
@@ -154,29 +324,155 @@ impl Item {
     ///
     /// The halted-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
     pub fn halted(&mut self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Halted(reason, eta));
+        self.set_state(State::Halted(reason, eta));
+    }
+
+    /// Call to indicate that the task finished successfully, marking it with the current time.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// The finished-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
+    pub fn finished(&mut self) {
+        self.set_state(State::Finished(SystemTime::now()));
+    }
+
+    /// Call to indicate that the task failed, marking it with the current time.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// The failed-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
+    pub fn failed(&mut self) {
+        self.set_state(State::Failed(SystemTime::now()));
+    }
+
+    /// Call to indicate that the task was cancelled before it could finish, marking it with the current time.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    /// The cancelled-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
+    pub fn cancelled(&mut self) {
+        self.set_state(State::Cancelled(SystemTime::now()));
+    }
+
+    /// Set the attribute with the given `key` to `value`, overwriting any previous value.
+    ///
+    /// Attributes provide structured information about a task, like the file it's working on,
+    /// and are made available in [snapshots](./struct.Root.html#method.sorted_snapshot).
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        if let Some(mut r) = self.tree.get_mut(&self.key) {
+            r.value_mut().task.attributes.insert(key.into(), value.into());
+            self.shared.changed();
+        };
+    }
+
+    /// Remove the attribute with the given `key`, returning its value if it was set.
+    pub fn remove_attribute(&mut self, key: &str) -> Option<String> {
+        let value = self
+            .tree
+            .get_mut(&self.key)
+            .and_then(|mut r| r.value_mut().task.attributes.remove(key));
+        if value.is_some() {
+            self.shared.changed();
+        }
+        value
+    }
+
+    /// Returns the value of the attribute with the given `key`, if it is set.
+    pub fn attribute(&self, key: &str) -> Option<String> {
+        self.tree
+            .get(&self.key)
+            .and_then(|r| r.value().task.attributes.get(key).cloned())
+    }
+
+    /// Returns true if cancellation of this task or one of its parents was requested,
+    /// for example using [`tree::Root::cancel(…)`](./struct.Root.html#method.cancel).
+    ///
+    /// Cancellation is cooperative: poll it in regular intervals and stop working once it returns true, possibly
+    /// calling [`cancelled()`](./struct.Item.html#method.cancelled) to indicate the task ended.
+    ///
+    /// This call never locks, making it safe to use in hot loops.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancellation_requested.load(Ordering::Relaxed)
+    }
+
+    /// Keep this task visible in the tree for the given `linger` duration after this `Item` was dropped,
+    /// so its final state can be seen, or remove it right away if `None`.
+    ///
+    /// It overrides [`tree::Options::linger`](./struct.Options.html#structfield.linger) for this item,
+    /// and children added afterwards inherit it.
+    pub fn set_linger(&mut self, linger: Option<Duration>) {
+        self.linger = linger;
+    }
+
+    /// If `enabled`, derive this task's step and upper bound from its direct children each time a snapshot is taken,
+    /// which costs nothing when making progress.
+    ///
+    /// Each child contributes its own step and upper bound, or its fraction of the weight set with
+    /// [`set_weight(…)`](./struct.Item.html#method.set_weight). If one of them is unbounded, so is this task.
+    /// Finished children count as fully done.
+    ///
+    /// This works for organizational items as well, which don't need `init(…)` to be called.
+    pub fn set_aggregate_children(&mut self, enabled: bool) {
+        if let Some(mut r) = self.tree.get_mut(&self.key) {
+            r.value_mut().aggregate_children = enabled;
+            self.shared.changed();
+        };
+    }
+
+    /// Set the `weight` with which this task contributes to its parent if it
+    /// [aggregates its children](./struct.Item.html#method.set_aggregate_children), or `None` to contribute
+    /// its step and upper bound as is.
+    ///
+    /// A weighted task contributes `weight` to the parent's upper bound, and the part of it matching its
+    /// own progress to the parent's step.
+    pub fn set_weight(&mut self, weight: Option<Step>) {
+        if let Some(mut r) = self.tree.get_mut(&self.key) {
+            r.value_mut().weight = weight;
+            self.shared.changed();
+        };
     }
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name`.
     ///
-    /// **Important**: The depth of the hierarchy is limited to [`tree::Key::max_level`](./struct.Key.html#method.max_level).
-    /// Exceeding the level will be ignored, and new tasks will be added to this instance's
-    /// level instead.
+    /// There is no limit to the depth of the hierarchy.
     pub fn add_child(&mut self, name: impl Into<String>) -> Item {
-        let child_key = self.key.add_child(self.highest_child_id);
-        self.tree.insert(
-            child_key,
-            Task {
-                name: name.into(),
-                progress: None,
-            },
-        );
-        self.highest_child_id = self.highest_child_id.wrapping_add(1);
+        let child_key = self.next_child_key();
+        let entry = Entry::new(name.into());
+        let shared = entry.shared.clone();
+        if self.is_cancelled() {
+            shared.cancellation_requested.store(true, Ordering::Relaxed);
+        }
+        self.tree.insert(child_key.clone(), entry);
+        self.changes.fetch_add(1, Ordering::Relaxed);
         Item {
             highest_child_id: 0,
             key: child_key,
+            shared,
             tree: self.tree.clone(),
             messages: self.messages.clone(),
+            linger: self.linger,
+            changes: self.changes.clone(),
+        }
+    }
+
+    /// Remove all tasks which lingered past their `Item` for long enough.
+    pub(crate) fn remove_expired(&self) {
+        let now = Instant::now();
+        let num_tasks = self.tree.len();
+        self.tree.retain(|_, entry| !entry.is_expired(now));
+        if self.tree.len() != num_tasks {
+            self.changes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Return a key for a new child which isn't used by any of our children that are still present in the tree.
+    ///
+    /// IDs are handed out in ascending order and wrap around eventually, at which point those still in use are skipped.
+    /// As only this instance creates children underneath its key, a key found to be unused stays unused until we insert it.
+    fn next_child_key(&mut self) -> Key {
+        loop {
+            let child_key = self.key.add_child(self.highest_child_id);
+            self.highest_child_id = self.highest_child_id.wrapping_add(1);
+            if !self.tree.contains_key(&child_key) {
+                break child_key;
+            }
         }
     }
 
@@ -186,21 +482,34 @@ impl Item {
     /// made, including indicating success or failure.
     pub fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         let message: String = message.into();
-        self.messages.lock().push_overwrite(
-            level,
-            {
-                let name = self.tree.get(&self.key).map(|v| v.name.to_owned()).unwrap_or_default();
-
-                #[cfg(feature = "progress-tree-log")]
-                match level {
-                    MessageLevel::Failure => crate::warn!("{} → {}", name, message),
-                    MessageLevel::Info | MessageLevel::Success => crate::info!("{} → {}", name, message),
-                };
-
-                name
-            },
-            message,
-        )
+        let mut path = Vec::with_capacity(self.key.level());
+        let mut key = Some(self.key.clone());
+        while let Some(k) = key {
+            if let Some(r) = self.tree.get(&k) {
+                path.push(r.value().task.name.to_owned());
+            }
+            key = k.parent();
+        }
+        path.reverse();
+
+        #[cfg(feature = "progress-tree-log")]
+        {
+            let name = path.last().map(String::as_str).unwrap_or_default();
+            match level {
+                MessageLevel::Debug => crate::debug!(target: LOG_TARGET, "{} → {}", name, message),
+                MessageLevel::Warning | MessageLevel::Failure => {
+                    crate::warn!(target: LOG_TARGET, "{} → {}", name, message)
+                }
+                MessageLevel::Info | MessageLevel::Success => {
+                    crate::info!(target: LOG_TARGET, "{} → {}", name, message)
+                }
+            };
+        }
+
+        self.messages
+            .lock()
+            .push_overwrite(level, self.key.clone(), path, message);
+        self.changes.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Create a message indicating the task is done
@@ -218,12 +527,34 @@ impl Item {
         self.message(MessageLevel::Info, message)
     }
 
+    /// Create a message providing detailed information for debugging the task.
+    pub fn debug(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Debug, message)
+    }
+
+    /// Create a message indicating a recoverable problem, like a retry or a fallback.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Warning, message)
+    }
+
     pub(crate) fn deep_clone(&self) -> Item {
+        let changes = Arc::new(AtomicU64::new(self.changes.load(Ordering::Relaxed)));
+        let tree: DashMap<_, _> = self
+            .tree
+            .iter()
+            .map(|r| (r.key().clone(), r.value().deep_clone()))
+            .collect();
         Item {
-            key: self.key,
+            key: self.key.clone(),
             highest_child_id: self.highest_child_id,
-            tree: Arc::new(self.tree.deref().clone()),
+            shared: tree
+                .get(&self.key)
+                .map(|r| r.value().shared.clone())
+                .unwrap_or_else(|| Arc::new(self.shared.deep_clone())),
+            tree: Arc::new(tree),
             messages: Arc::new(Mutex::new(self.messages.lock().clone())),
+            linger: self.linger,
+            changes,
         }
     }
 }
@@ -270,4 +601,24 @@ impl crate::Progress for Item {
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         Item::message(self, level, message)
     }
+
+    fn is_cancelled(&self) -> bool {
+        Item::is_cancelled(self)
+    }
+
+    fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        Item::set_attribute(self, key, value)
+    }
+
+    fn finished(&mut self) {
+        Item::finished(self)
+    }
+
+    fn failed(&mut self) {
+        Item::failed(self)
+    }
+
+    fn cancelled(&mut self) {
+        Item::cancelled(self)
+    }
 }
@@ -0,0 +1,133 @@
+use crate::{
+    messages::{Message, MessageLevel},
+    tree::{item::LOG_TARGET, Root},
+};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+    time::SystemTime,
+};
+
+/// All captures which exist, along with their id, with the most recent one capturing log records.
+static CAPTURES: RwLock<Vec<(u64, Root)>> = RwLock::new(Vec::new());
+/// The id of the next capture.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The global logger, routing records into the capturing tree if there is one, or to the fallback otherwise.
+struct Dispatch {
+    fallback: Option<Box<dyn log::Log>>,
+}
+
+fn capturing() -> Option<Root> {
+    CAPTURES
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .last()
+        .map(|(_, root)| root.clone())
+}
+
+fn message_level(level: log::Level) -> MessageLevel {
+    match level {
+        log::Level::Error => MessageLevel::Failure,
+        log::Level::Warn => MessageLevel::Warning,
+        log::Level::Info => MessageLevel::Info,
+        log::Level::Debug | log::Level::Trace => MessageLevel::Debug,
+    }
+}
+
+impl log::Log for Dispatch {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        match capturing() {
+            Some(_) => metadata.target() != LOG_TARGET,
+            None => matches!(&self.fallback, Some(fallback) if fallback.enabled(metadata)),
+        }
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        match capturing() {
+            Some(root) => {
+                if record.target() == LOG_TARGET {
+                    // The message is already in the tree, which sent it.
+                    return;
+                }
+                let inner = root.inner.lock();
+                inner.messages.lock().push(Message {
+                    time: SystemTime::now(),
+                    level: message_level(record.level()),
+                    origin: record.target().to_owned(),
+                    key: Default::default(),
+                    path: Vec::new(),
+                    message: record.args().to_string(),
+                });
+                inner.changes.fetch_add(1, Ordering::Relaxed);
+            }
+            None => {
+                if let Some(fallback) = &self.fallback {
+                    fallback.log(record)
+                }
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(fallback) = &self.fallback {
+            fallback.flush()
+        }
+    }
+}
+
+/// Captures all records of the `log` crate into the message buffer of a tree while it exists.
+///
+/// It's created by [`Root::capture_log()`](./struct.Root.html#method.capture_log), and only has an effect once
+/// [`LogCapture::install(…)`](#method.install) was called. Log records become messages without a task,
+/// using the target of the record as origin.
+///
+/// That way, while a renderer like the TUI is running, it can show all log records without them corrupting the terminal.
+///
+/// ```rust
+/// let tree = prodash::Tree::new();
+/// prodash::tree::LogCapture::install(None, log::LevelFilter::Info).ok();
+/// let capture = tree.capture_log();
+/// log::info!("shown as message in the tree");
+/// drop(capture);
+/// log::info!("sent to the fallback logger");
+/// ```
+#[must_use = "log records are only captured until this instance is dropped"]
+pub struct LogCapture {
+    id: u64,
+}
+
+impl LogCapture {
+    /// Install the global logger which captures log records while a `LogCapture` exists, up to the given `max_level`.
+    ///
+    /// At all other times, records are passed to the `fallback` logger, if there is one.
+    /// As there can only be one global logger, this fails if one was installed before.
+    pub fn install(
+        fallback: Option<Box<dyn log::Log>>,
+        max_level: log::LevelFilter,
+    ) -> Result<(), log::SetLoggerError> {
+        log::set_boxed_logger(Box::new(Dispatch { fallback }))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    pub(crate) fn new(root: Root) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        CAPTURES.write().unwrap_or_else(|err| err.into_inner()).push((id, root));
+        LogCapture { id }
+    }
+}
+
+/// Ends this capture, no matter whether it is the most recent one or not.
+///
+/// If it was, the previous capture which still exists takes over, or log records are passed to the fallback logger again.
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        CAPTURES
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain(|(id, _)| *id != self.id);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::messages::{Message, MessageLevel};
+use parking_lot::Mutex;
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::SystemTime,
+};
+
+/// Configures where to write all messages of a tree to, one line per message, see
+/// [`tree::Options::message_log`](./struct.Options.html#structfield.message_log).
+///
+/// ```rust,no_run
+/// use prodash::{tree::MessageLog, TreeOptions};
+/// let tree = TreeOptions {
+///     message_log: Some(MessageLog::to_file("messages.log").rotate_at(10 * 1024 * 1024, 3)),
+///     ..TreeOptions::default()
+/// }.create();
+/// ```
+#[derive(Clone)]
+pub struct MessageLog {
+    target: Target,
+    rotation: Option<Rotation>,
+}
+
+#[derive(Clone)]
+enum Target {
+    File(PathBuf),
+    Writer(Arc<Mutex<Option<Box<dyn Write + Send>>>>),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Rotation {
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl fmt::Debug for MessageLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Target::File(path) => f.write_fmt(format_args!("MessageLog::File({:?})", path)),
+            Target::Writer(_) => f.write_str("MessageLog::Writer(..)"),
+        }
+    }
+}
+
+/// Initialization
+impl MessageLog {
+    /// Append all messages to the file at `path`, creating it if needed.
+    pub fn to_file(path: impl Into<PathBuf>) -> Self {
+        MessageLog {
+            target: Target::File(path.into()),
+            rotation: None,
+        }
+    }
+
+    /// Write all messages to the given `writer`.
+    ///
+    /// Note that only the first tree created with this configuration will write to it.
+    pub fn to_writer(writer: impl Write + Send + 'static) -> Self {
+        MessageLog {
+            target: Target::Writer(Arc::new(Mutex::new(Some(Box::new(writer))))),
+            rotation: None,
+        }
+    }
+
+    /// Once the log file grows beyond `max_bytes`, rename it by appending `.1` to its name and start a new one,
+    /// keeping at most `keep` of these rotated files, where `.1` is the most recent one.
+    ///
+    /// This has no effect when writing to a writer.
+    pub fn rotate_at(mut self, max_bytes: u64, keep: usize) -> Self {
+        self.rotation = Some(Rotation { max_bytes, keep });
+        self
+    }
+}
+
+impl MessageLog {
+    /// Spawn a thread writing all messages received by `messages` until it disconnects, or until writing fails.
+    pub(crate) fn spawn(self, messages: mpsc::Receiver<Message>) -> std::thread::JoinHandle<io::Result<()>> {
+        std::thread::spawn(move || match self.target {
+            Target::File(path) => write_to_file(&path, self.rotation, messages),
+            Target::Writer(writer) => match writer.lock().take() {
+                Some(mut writer) => write_all(&mut writer, &messages, None).map(|_| ()),
+                None => Ok(()),
+            },
+        })
+    }
+}
+
+fn write_to_file(path: &Path, rotation: Option<Rotation>, messages: mpsc::Receiver<Message>) -> io::Result<()> {
+    loop {
+        let file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        let mut out = io::BufWriter::new(file);
+        match write_all(&mut out, &messages, rotation.map(|r| (r.max_bytes, written)))? {
+            Outcome::Disconnected => return Ok(()),
+            Outcome::SizeExceeded => {
+                out.flush()?;
+                drop(out);
+                rotate(path, rotation.map(|r| r.keep).unwrap_or(0))?;
+            }
+        }
+    }
+}
+
+fn rotate(path: &Path, keep: usize) -> io::Result<()> {
+    let numbered = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+    for n in (1..keep).rev() {
+        let from = numbered(n);
+        if from.exists() {
+            fs::rename(from, numbered(n + 1))?;
+        }
+    }
+    fs::rename(path, numbered(1))
+}
+
+enum Outcome {
+    Disconnected,
+    SizeExceeded,
+}
+
+/// Write messages to `out` until the sender disconnects, or until the `size_limit` of `(max_bytes, written)` is reached,
+/// with `written` bytes already being present.
+fn write_all(
+    out: &mut dyn Write,
+    messages: &mpsc::Receiver<Message>,
+    size_limit: Option<(u64, u64)>,
+) -> io::Result<Outcome> {
+    let mut written = size_limit.map(|(_, written)| written).unwrap_or(0);
+    let mut line = String::new();
+    loop {
+        let message = match messages.try_recv() {
+            Ok(message) => message,
+            Err(mpsc::TryRecvError::Empty) => {
+                out.flush()?;
+                match messages.recv() {
+                    Ok(message) => message,
+                    Err(_) => return Ok(Outcome::Disconnected),
+                }
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                out.flush()?;
+                return Ok(Outcome::Disconnected);
+            }
+        };
+        line.clear();
+        format_message(&message, &mut line);
+        out.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+        if let Some((max_bytes, _)) = size_limit {
+            if written >= max_bytes {
+                return Ok(Outcome::SizeExceeded);
+            }
+        }
+    }
+}
+
+/// Format `message` as a single line, like `2020-10-18T12:34:56.789Z WARNING fetch::clone → retrying`.
+fn format_message(message: &Message, out: &mut String) {
+    use fmt::Write;
+    format_rfc3339_millis(message.time, out);
+    let origin = if message.path.is_empty() {
+        message.origin.clone()
+    } else {
+        message.path.join("::")
+    };
+    write!(
+        out,
+        " {:<7} {} → {}",
+        match message.level {
+            MessageLevel::Debug => "DEBUG",
+            MessageLevel::Info => "INFO",
+            MessageLevel::Success => "SUCCESS",
+            MessageLevel::Warning => "WARNING",
+            MessageLevel::Failure => "FAILURE",
+        },
+        origin,
+        message.message.replace('\n', "\\n")
+    )
+    .expect("writing to a string never fails");
+    out.push('\n');
+}
+
+/// Write `time` as UTC timestamp with millisecond precision.
+pub(crate) fn format_rfc3339_millis(time: SystemTime, out: &mut String) {
+    use fmt::Write;
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    write!(
+        out,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+    .expect("writing to a string never fails");
+}
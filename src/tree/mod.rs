@@ -6,3 +6,16 @@ pub use root::{Options, Root};
 
 mod item;
 pub use item::Item;
+
+mod message_log;
+pub use message_log::MessageLog;
+
+#[cfg(feature = "progress-tree-log")]
+mod log_capture;
+#[cfg(feature = "progress-tree-log")]
+pub use log_capture::LogCapture;
+
+#[cfg(feature = "progress-tree-tracing")]
+pub mod tracing;
+#[cfg(feature = "progress-tree-tracing")]
+pub use self::tracing::TracingLayer;
@@ -1,16 +1,45 @@
 use crate::{
     messages::{Message, MessageCopyState, MessageRingBuffer},
-    progress::{Key, Task},
-    tree::Item,
+    progress::{Key, State, Step, Task, Value},
+    tree::{
+        item::{Entry, Shared},
+        Item, MessageLog,
+    },
 };
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 /// The top-level of the progress tree.
 #[derive(Clone, Debug)]
 pub struct Root {
     pub(crate) inner: Arc<Mutex<Item>>,
+    generation: Arc<Mutex<Generation>>,
+    /// The thread writing the message log, until it was shut down.
+    message_log: Arc<Mutex<Option<MessageLogWriter>>>,
+}
+
+#[derive(Debug)]
+struct MessageLogWriter {
+    /// The id of the subscription to the messages of the tree.
+    subscription: u64,
+    thread: JoinHandle<io::Result<()>>,
+}
+
+/// The generation of the tree as computed last.
+#[derive(Debug, Default)]
+struct Generation {
+    /// The amount of changes to the structure of the tree and its messages at the time.
+    changes: u64,
+    current: u64,
 }
 
 impl Default for Root {
@@ -33,10 +62,16 @@ impl Root {
         self.inner.lock().messages.lock().buf.capacity()
     }
 
-    /// Returns the current amount of `Item`s stored in the tree.
+    /// Returns the current amount of `Item`s stored in the tree, including dropped ones which still linger.
     /// **Note** that this is at most a guess as tasks can be added and removed in parallel.
     pub fn num_tasks(&self) -> usize {
-        self.inner.lock().tree.len()
+        let now = Instant::now();
+        self.inner
+            .lock()
+            .tree
+            .iter()
+            .filter(|entry| !entry.value().is_expired(now))
+            .count()
     }
 
     /// Adds a new child `tree::Item`, whose parent is this instance, with the given `name`.
@@ -49,10 +84,91 @@ impl Root {
 
     /// Copy the entire progress tree into the given `out` vector, so that
     /// it can be traversed from beginning to end in order of hierarchy.
+    ///
+    /// Tasks which lingered past their `Item` for long enough are removed from the tree beforehand, and
+    /// tasks [aggregating their children](./struct.Item.html#method.set_aggregate_children) have their progress computed.
     pub fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
         out.clear();
-        out.extend(self.inner.lock().tree.iter().map(|r| (*r.key(), r.value().clone())));
-        out.sort_by_key(|t| t.0);
+        let inner = self.inner.lock();
+        inner.remove_expired();
+        // Both refer to positions in `out` before it is sorted, to avoid cloning keys.
+        let mut aggregating = Vec::new();
+        let mut weights = Vec::new();
+        out.extend(inner.tree.iter().enumerate().map(|(index, r)| {
+            let entry = r.value();
+            if entry.aggregate_children {
+                aggregating.push(index);
+            }
+            if let Some(weight) = entry.weight {
+                weights.push((index, weight));
+            }
+            (r.key().clone(), entry.snapshot())
+        }));
+        if aggregating.is_empty() {
+            out.sort_by(|a, b| a.0.cmp(&b.0));
+            return;
+        }
+        let mut order: Vec<_> = (0..out.len()).collect();
+        order.sort_by(|a, b| out[*a].0.cmp(&out[*b].0));
+        let mut sorted_index = vec![0; order.len()];
+        for (to, from) in order.into_iter().enumerate() {
+            sorted_index[from] = to;
+        }
+        // Keys are unique, so this sorts `out` just like `order`.
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        for index in aggregating.iter_mut() {
+            *index = sorted_index[*index];
+        }
+        for (index, _) in weights.iter_mut() {
+            *index = sorted_index[*index];
+        }
+        weights.sort_unstable_by_key(|(index, _)| *index);
+        aggregate_children(out, aggregating, &weights);
+    }
+
+    /// Returns a number which changes whenever a task is added, altered or removed, or a message is sent.
+    ///
+    /// Renderers can compare it to the generation of their last frame to avoid redrawing when nothing changed.
+    /// It never decreases.
+    ///
+    /// **Note** that it visits all tasks to see which of them changed, which keeps making progress free of contention.
+    pub fn generation(&self) -> u64 {
+        let mut generation = self.generation.lock();
+        let inner = self.inner.lock();
+        let changes = inner.changes.load(Ordering::Relaxed);
+        let mut changed = changes != generation.changes;
+        for r in inner.tree.iter() {
+            changed |= r.value().shared.take_changed();
+        }
+        if changed {
+            generation.changes = changes;
+            generation.current += 1;
+        }
+        generation.current
+    }
+
+    /// Request cancellation of the task with the given `key` and all tasks underneath it, including the ones added later.
+    ///
+    /// Tasks learn about it by polling [`Item::is_cancelled()`](./struct.Item.html#method.is_cancelled).
+    pub fn cancel(&self, key: &Key) {
+        let inner = self.inner.lock();
+        for entry in inner
+            .tree
+            .iter()
+            .filter(|r| r.key() == key || r.key().is_descendant_of(key))
+        {
+            let shared = &entry.value().shared;
+            shared.cancellation_requested.store(true, Ordering::Relaxed);
+            shared.changed();
+        }
+    }
+
+    /// Capture all records of the `log` crate as messages of this tree until the returned instance is dropped.
+    ///
+    /// This only has an effect if [`LogCapture::install(…)`](./struct.LogCapture.html#method.install) was called before.
+    #[cfg(feature = "progress-tree-log")]
+    pub fn capture_log(&self) -> crate::tree::LogCapture {
+        crate::tree::LogCapture::new(self.clone())
     }
 
     /// Copy all messages from the internal ring buffer into the given `out`
@@ -61,19 +177,125 @@ impl Root {
         self.inner.lock().messages.lock().copy_all(out);
     }
 
+    /// Copy all messages sent by the task with the given `key`, or by any task underneath it if `include_subtree` is true,
+    /// into the given `out` vector. Messages are ordered from oldest to newest.
+    pub fn copy_messages_from(&self, key: &Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.inner.lock().messages.lock().copy_from(key, include_subtree, out);
+    }
+
+    /// Return a receiver for all messages sent from now on, each of which it receives exactly once.
+    ///
+    /// As opposed to [`copy_new_messages(…)`](#method.copy_new_messages), no message is lost if more of them
+    /// are sent than fit into the message buffer. Instead they queue up until they are received.
+    pub fn subscribe_messages(&self) -> std::sync::mpsc::Receiver<Message> {
+        self.inner.lock().messages.lock().subscribe()
+    }
+
+    /// Like [`subscribe_messages()`](#method.subscribe_messages), but return a `Stream` of messages instead.
+    #[cfg(feature = "progress-tree-message-stream")]
+    pub fn subscribe_messages_stream(&self) -> impl futures_core::Stream<Item = Message> {
+        self.inner.lock().messages.lock().subscribe_stream()
+    }
+
     /// Copy only new messages from the internal ring buffer into the given `out`
     /// vector. Messages are ordered from oldest to newest.
     pub fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
         self.inner.lock().messages.lock().copy_new(out, prev)
     }
 
+    /// Stop writing to the [message log](./struct.Options.html#structfield.message_log) once all messages sent so far
+    /// are written, and wait for that to happen, returning the first error that occurred while writing, if any.
+    ///
+    /// Call it before the program exits to be sure no message is lost. Messages sent afterwards aren't written anymore,
+    /// and calling it again does nothing, just like it does if there is no message log.
+    pub fn shutdown_message_log(&self) -> io::Result<()> {
+        match self.message_log.lock().take() {
+            Some(MessageLogWriter { subscription, thread }) => {
+                self.inner.lock().messages.lock().unsubscribe(subscription);
+                thread
+                    .join()
+                    .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::Other, "message log thread panicked")))
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Duplicate all content and return it.
     ///
     /// This is an expensive operation, whereas `clone()` is not as it is shallow.
     pub fn deep_clone(&self) -> Root {
+        let inner = self.inner.lock().deep_clone();
         Root {
-            inner: Arc::new(Mutex::new(self.inner.lock().deep_clone())),
+            inner: Arc::new(Mutex::new(inner)),
+            generation: Default::default(),
+            message_log: Default::default(),
+        }
+    }
+}
+
+/// Derive the progress of all tasks at `parent_indices` from their direct children in `sorted`,
+/// with `sorted_weights` holding the weights of tasks by their index.
+///
+/// Parents are handled from the bottom of the hierarchy upwards, so that aggregates can be aggregated in turn.
+fn aggregate_children(sorted: &mut [(Key, Task)], mut parent_indices: Vec<usize>, sorted_weights: &[(usize, Step)]) {
+    parent_indices.sort_unstable();
+
+    for parent_index in parent_indices.into_iter().rev() {
+        let (parent, descendants) = sorted[parent_index..]
+            .split_first_mut()
+            .expect("parent index in bounds");
+        let child_level = parent.0.level() + 1;
+        let mut contributions = descendants
+            .iter()
+            .enumerate()
+            .take_while(|(_, (key, _))| key.is_descendant_of(&parent.0))
+            .filter(|(_, (key, _))| key.level() == child_level)
+            .filter_map(|(offset, (_, task))| {
+                task.progress.as_ref().map(|progress| {
+                    let index = parent_index + 1 + offset;
+                    let weight = sorted_weights
+                        .binary_search_by_key(&index, |(index, _)| *index)
+                        .ok()
+                        .map(|index| sorted_weights[index].1);
+                    contribution(progress, weight)
+                })
+            })
+            .peekable();
+        if contributions.peek().is_none() {
+            continue;
         }
+        let (step, done_at) = contributions.fold(
+            (0, Some(0)),
+            |(step, done_at): (Step, Option<Step>), (child_step, child_done_at)| {
+                (
+                    step.saturating_add(child_step),
+                    done_at
+                        .and_then(|done_at| child_done_at.map(|child_done_at| done_at.saturating_add(child_done_at))),
+                )
+            },
+        );
+        let progress = parent.1.progress.get_or_insert_with(Value::default);
+        progress.step = step;
+        progress.done_at = done_at;
+    }
+}
+
+/// Returns the step and upper bound the given child `progress` contributes to its parent.
+fn contribution(progress: &Value, weight: Option<Step>) -> (Step, Option<Step>) {
+    let finished = matches!(progress.state, State::Finished(_));
+    match weight {
+        Some(weight) => {
+            let fraction = if finished {
+                1.0
+            } else {
+                progress.fraction().unwrap_or(0.0).min(1.0)
+            };
+            ((weight as f64 * fraction as f64).round() as Step, Some(weight))
+        }
+        None => match progress.done_at {
+            Some(done_at) if finished => (done_at.max(progress.step), Some(done_at)),
+            done_at => (progress.step, done_at),
+        },
     }
 }
 
@@ -89,6 +311,19 @@ pub struct Options {
     pub initial_capacity: usize,
     /// The amount of messages we can hold before we start overwriting old ones
     pub message_buffer_capacity: usize,
+    /// If set, _(default: None)_, tasks stay in the tree for the given duration after their `Item` was dropped,
+    /// marked with the time they were dropped at, to allow renderers to show their final state.
+    ///
+    /// They are removed once they expired, the next time a snapshot is taken or another lingering item is dropped.
+    /// It can be overridden per item using [`Item::set_linger(…)`](./struct.Item.html#method.set_linger).
+    pub linger: Option<Duration>,
+    /// If set, _(default: None)_, every message is written to the given log, one line per message,
+    /// independently of the `message_buffer_capacity`.
+    ///
+    /// Messages are written from a background thread that stops once the tree is dropped, or once writing fails.
+    /// Call [`Root::shutdown_message_log()`](./struct.Root.html#method.shutdown_message_log) before the program exits
+    /// to be sure all messages were written, and to learn about errors.
+    pub message_log: Option<MessageLog>,
 }
 
 impl Options {
@@ -104,6 +339,8 @@ impl Default for Options {
         Options {
             initial_capacity: 100,
             message_buffer_capacity: 20,
+            linger: None,
+            message_log: None,
         }
     }
 }
@@ -113,15 +350,30 @@ impl From<Options> for Root {
         Options {
             initial_capacity,
             message_buffer_capacity,
+            linger,
+            message_log,
         }: Options,
     ) -> Self {
+        let mut messages = MessageRingBuffer::with_capacity(message_buffer_capacity);
+        let message_log = message_log.map(|message_log| {
+            let (subscription, receiver) = messages.subscribe_with_id();
+            MessageLogWriter {
+                subscription,
+                thread: message_log.spawn(receiver),
+            }
+        });
         Root {
             inner: Arc::new(Mutex::new(Item {
                 highest_child_id: 0,
                 key: Key::default(),
-                tree: Arc::new(DashMap::with_capacity(initial_capacity)),
-                messages: Arc::new(Mutex::new(MessageRingBuffer::with_capacity(message_buffer_capacity))),
+                shared: Arc::new(Shared::default()),
+                tree: Arc::new(DashMap::<Key, Entry>::with_capacity(initial_capacity)),
+                messages: Arc::new(Mutex::new(messages)),
+                linger,
+                changes: Arc::new(AtomicU64::default()),
             })),
+            generation: Default::default(),
+            message_log: Arc::new(Mutex::new(message_log)),
         }
     }
 }
@@ -146,4 +398,16 @@ impl crate::Root for Root {
     fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
         self.copy_new_messages(out, prev)
     }
+
+    fn copy_messages_from(&self, key: &Key, include_subtree: bool, out: &mut Vec<Message>) {
+        self.copy_messages_from(key, include_subtree, out)
+    }
+
+    fn cancel(&self, key: &Key) {
+        self.cancel(key)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation())
+    }
 }
@@ -2,7 +2,7 @@ mod message_buffer {
     use crate::messages::{Message, MessageLevel, MessageRingBuffer};
 
     fn push(buf: &mut MessageRingBuffer, msg: impl Into<String>) {
-        buf.push_overwrite(MessageLevel::Info, "test".into(), msg);
+        buf.push_overwrite(MessageLevel::Info, Default::default(), vec!["test".into()], msg);
     }
     fn push_and_copy_all(buf: &mut MessageRingBuffer, msg: impl Into<String>, out: &mut Vec<Message>) {
         push(buf, msg);
@@ -114,6 +114,18 @@ mod message_buffer {
     }
 }
 
+mod message_level {
+    use crate::messages::MessageLevel::*;
+
+    #[test]
+    fn levels_are_ordered_by_severity() {
+        assert!(Debug < Info);
+        assert!(Info < Success);
+        assert!(Success < Warning);
+        assert!(Warning < Failure);
+    }
+}
+
 mod key_adjacency {
     use crate::progress::{
         key::{Adjacency, SiblingLocation::*},
@@ -122,7 +134,7 @@ mod key_adjacency {
 
     fn to_kv(keys: &[Key]) -> Vec<(Key, Task)> {
         let mut v: Vec<_> = keys.iter().map(|k| (k.to_owned(), Task::default())).collect();
-        v.sort_by_key(|v| v.0);
+        v.sort_by(|a, b| a.0.cmp(&b.0));
         v
     }
 
@@ -136,10 +148,10 @@ mod key_adjacency {
         let p2 = r.add_child(2);
         to_kv(
             &[
-                p1,
+                p1.clone(),
                 p1.add_child(1),
                 p1.add_child(2),
-                p2,
+                p2.clone(),
                 p2.add_child(1),
                 p2.add_child(2),
             ][..],
@@ -150,7 +162,7 @@ mod key_adjacency {
         let r = Key::default();
         let p1 = r.add_child(1);
         let p2 = p1.add_child(2);
-        to_kv(&[p1, p2, p2.add_child(1)][..])
+        to_kv(&[p1, p2.clone(), p2.add_child(1)][..])
     }
 
     fn root_with_three_levels_two_siblings_on_level_2() -> Vec<(Key, Task)> {
@@ -158,72 +170,42 @@ mod key_adjacency {
         let p1 = r.add_child(1);
         let p11 = p1.add_child(1);
         let p12 = p1.add_child(2);
-        to_kv(&[p1, p11, p11.add_child(1), p12, p12.add_child(1)][..])
+        to_kv(&[p1, p11.clone(), p11.add_child(1), p12.clone(), p12.add_child(1)][..])
     }
 
     #[test]
     fn root_level() {
         let entries = root_with_two_children();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
-        );
-        assert_eq!(
-            Key::adjacency(&entries, 1),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![AboveAndBelow]));
+        assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![Above]));
     }
 
     #[test]
     fn level_2_two_siblings() {
         let entries = root_with_two_children_with_two_children();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![AboveAndBelow]));
         {
             assert_eq!(
                 Key::adjacency(&entries, 1),
-                Adjacency(AboveAndBelow, AboveAndBelow, NotFound, NotFound)
-            );
-            assert_eq!(
-                Key::adjacency(&entries, 2),
-                Adjacency(AboveAndBelow, Above, NotFound, NotFound)
+                Adjacency(vec![AboveAndBelow, AboveAndBelow])
             );
+            assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![AboveAndBelow, Above]));
         }
-        assert_eq!(
-            Key::adjacency(&entries, 3),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 3), Adjacency(vec![Above]));
         {
-            assert_eq!(
-                Key::adjacency(&entries, 4),
-                Adjacency(NotFound, AboveAndBelow, NotFound, NotFound)
-            );
-            assert_eq!(
-                Key::adjacency(&entries, 5),
-                Adjacency(NotFound, Above, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 4), Adjacency(vec![NotFound, AboveAndBelow]));
+            assert_eq!(Key::adjacency(&entries, 5), Adjacency(vec![NotFound, Above]));
         }
     }
 
     #[test]
     fn level_3_single_sibling() {
         let entries = root_with_three_levels();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![Above]));
         {
-            assert_eq!(
-                Key::adjacency(&entries, 1),
-                Adjacency(NotFound, Above, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![NotFound, Above]));
             {
-                assert_eq!(
-                    Key::adjacency(&entries, 2),
-                    Adjacency(NotFound, NotFound, Above, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![NotFound, NotFound, Above]));
             }
         }
     }
@@ -232,31 +214,19 @@ mod key_adjacency {
     fn level_3_two_siblings() {
         let entries = root_with_three_levels_two_siblings_on_level_2();
         {
-            assert_eq!(
-                Key::adjacency(&entries, 0),
-                Adjacency(Above, NotFound, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![Above]));
             {
-                assert_eq!(
-                    Key::adjacency(&entries, 1),
-                    Adjacency(NotFound, AboveAndBelow, NotFound, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![NotFound, AboveAndBelow]));
                 {
                     assert_eq!(
                         Key::adjacency(&entries, 2),
-                        Adjacency(NotFound, AboveAndBelow, Above, NotFound)
+                        Adjacency(vec![NotFound, AboveAndBelow, Above])
                     );
                 }
 
-                assert_eq!(
-                    Key::adjacency(&entries, 3),
-                    Adjacency(NotFound, Above, NotFound, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 3), Adjacency(vec![NotFound, Above]));
                 {
-                    assert_eq!(
-                        Key::adjacency(&entries, 4),
-                        Adjacency(NotFound, NotFound, Above, NotFound)
-                    );
+                    assert_eq!(Key::adjacency(&entries, 4), Adjacency(vec![NotFound, NotFound, Above]));
                 }
             }
         }
@@ -269,18 +239,1043 @@ mod key_adjacency {
             1,
             (Key::default().add_child(0).add_child(0).add_child(1), Task::default()),
         );
-        entries.sort_by_key(|v| v.0);
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![AboveAndBelow]),);
+        assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![AboveAndBelow]));
+        assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![Above]));
+    }
+
+    #[test]
+    fn level_8_two_siblings() {
+        let mut keys = vec![Key::default().add_child(1)];
+        for id in 2..=8 {
+            let child = keys.last().expect("one key").add_child(id);
+            keys.push(child);
+        }
+        keys.push(keys[6].add_child(9));
+        let entries = to_kv(&keys);
+        assert_eq!(entries[7].0.level(), 8);
+        assert_eq!(
+            Key::adjacency(&entries, 7),
+            Adjacency(vec![
+                NotFound,
+                NotFound,
+                NotFound,
+                NotFound,
+                NotFound,
+                NotFound,
+                NotFound,
+                AboveAndBelow
+            ])
+        );
+        assert_eq!(
+            Key::adjacency(&entries, 8),
+            Adjacency(vec![
+                NotFound, NotFound, NotFound, NotFound, NotFound, NotFound, NotFound, Above
+            ])
+        );
+    }
+}
+
+mod snapshot_diff {
+    use crate::{
+        progress::{Change, SnapshotDiff},
+        Tree,
+    };
+
+    fn changes(
+        previous: &[(crate::progress::Key, crate::progress::Task)],
+        current: &[(crate::progress::Key, crate::progress::Task)],
+    ) -> Vec<String> {
+        SnapshotDiff::new(previous, current)
+            .map(|(_, change)| match change {
+                Change::Added(task) => format!("added {}", task.name),
+                Change::Removed(task) => format!("removed {}", task.name),
+                Change::Renamed { from, to } => format!("renamed {} to {}", from, to),
+                Change::Progressed { from, to } => {
+                    format!("progressed from {:?} to {:?}", from.map(|v| v.step), to.map(|v| v.step))
+                }
+                Change::Altered { to, .. } => format!("altered {}", to.name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let root = Tree::new();
+        let _a = root.add_child("a");
+        let (mut previous, mut current) = (Vec::new(), Vec::new());
+        root.sorted_snapshot(&mut previous);
+        root.sorted_snapshot(&mut current);
+        assert!(changes(&previous, &current).is_empty());
+    }
+
+    #[test]
+    fn all_kinds_of_changes_are_reported_in_order_of_keys() {
+        let root = Tree::new();
+        let a = root.add_child("a");
+        let mut b = root.add_child("b");
+        b.init(None, None);
+        let (mut previous, mut current) = (Vec::new(), Vec::new());
+        root.sorted_snapshot(&mut previous);
+
+        drop(a);
+        b.set_name("B");
+        b.inc_by(2);
+        b.set_attribute("file", "x");
+        let _c = root.add_child("c");
+        root.sorted_snapshot(&mut current);
+
+        assert_eq!(
+            changes(&previous, &current),
+            vec![
+                "removed a",
+                "renamed b to B",
+                "progressed from Some(0) to Some(2)",
+                "altered B",
+                "added c"
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use crate::{
+        messages::{Message, MessageLevel},
+        progress::{Key, State, Task},
+        unit::{self, display},
+        Tree,
+    };
+    use std::time::{Duration, SystemTime};
+
+    fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+        let json = serde_json::to_string(value).expect("serialization to work");
+        serde_json::from_str(&json).expect("deserialization to work")
+    }
+
+    #[test]
+    fn snapshots_round_trip() {
+        let root = Tree::new();
+        let mut task = root.add_child("task");
+        task.init(
+            Some(10),
+            Some(unit::label_and_mode("files", display::Mode::with_percentage())),
+        );
+        task.inc_by(4);
+        task.set_attribute("file", "a.txt");
+        task.halted(
+            "waiting for lock",
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10)),
+        );
+        let _organizational = task.add_child("organizational");
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let restored: Vec<(Key, Task)> = round_trip(&snapshot);
+
+        assert_eq!(restored.len(), snapshot.len());
+        for ((key, task), (restored_key, restored_task)) in snapshot.iter().zip(&restored) {
+            assert_eq!(key, restored_key);
+            assert_eq!(task.name, restored_task.name);
+            assert_eq!(task.attributes, restored_task.attributes);
+            assert_eq!(task.dropped_at, restored_task.dropped_at);
+            assert_eq!(task.progress.is_some(), restored_task.progress.is_some());
+            if let (Some(value), Some(restored_value)) = (&task.progress, &restored_task.progress) {
+                assert_eq!(value.step, restored_value.step);
+                assert_eq!(value.done_at, restored_value.done_at);
+                assert_eq!(value.state, restored_value.state);
+                assert_eq!(value.initialized_at, restored_value.initialized_at);
+                assert_eq!(value.last_advanced_at, restored_value.last_advanced_at);
+                let display = |value: &crate::progress::Value| {
+                    value
+                        .unit
+                        .as_ref()
+                        .map(|unit| unit.display(value.step, value.done_at, None).to_string())
+                };
+                assert_eq!(display(value), display(restored_value));
+            }
+        }
+        assert_eq!(
+            restored[0].1.progress.as_ref().map(|v| v.state),
+            Some(State::Halted(
+                "waiting for lock",
+                Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10))
+            ))
+        );
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let root = Tree::new();
+        let mut task = root.add_child("task");
+        task.warn("careful");
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        let restored: Vec<Message> = round_trip(&messages);
+        assert_eq!(restored, messages);
+        assert_eq!(restored[0].level, MessageLevel::Warning);
+    }
+}
+
+mod message_log {
+    use crate::{tree::MessageLog, TreeOptions};
+    use parking_lot::Mutex;
+    use std::{
+        io,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn timestamps_are_utc_with_millisecond_precision() {
+        let mut out = String::new();
+        super::super::message_log::format_rfc3339_millis(
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_602_000_000_500),
+            &mut out,
+        );
+        assert_eq!(out, "2020-10-06T16:00:00.500Z");
+    }
+
+    #[test]
+    fn all_messages_are_written_to_the_sink_one_per_line() {
+        let buf = SharedBuf::default();
+        let root = TreeOptions {
+            message_buffer_capacity: 1,
+            message_log: Some(MessageLog::to_writer(buf.clone())),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut parent = root.add_child("fetch");
+        let mut child = parent.add_child("clone");
+        child.warn("retrying\nonce more");
+        for _ in 0..10 {
+            parent.info("progress");
+        }
+
+        root.shutdown_message_log().expect("writing succeeds");
+        child.info("not written after shutting down");
+        assert!(root.shutdown_message_log().is_ok(), "shutting down again does nothing");
+
+        let lines = String::from_utf8(buf.0.lock().clone()).expect("utf8");
         assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound),
+            lines.lines().count(),
+            11,
+            "all messages are written once the log is shut down"
         );
+        let first = lines.lines().next().expect("one line");
+        assert_eq!(&first[10..11], "T");
+        assert_eq!(&first[23..24], "Z");
+        assert_eq!(&first[24..], " WARNING fetch::clone → retrying\\nonce more");
+        assert!(lines.lines().skip(1).all(|l| l.ends_with(" INFO    fetch → progress")));
+    }
+
+    #[test]
+    fn log_files_are_rotated_once_they_get_too_large() {
+        let dir = std::env::temp_dir().join(format!("prodash-message-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        let path = dir.join("messages.log");
+        let root = TreeOptions {
+            message_log: Some(MessageLog::to_file(&path).rotate_at(100, 2)),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut task = root.add_child("task");
+        for id in 0..20 {
+            task.info(format!("message {}", id));
+        }
+        root.shutdown_message_log().expect("writing succeeds");
+
+        let rotated = |n: usize| dir.join(format!("messages.log.{}", n));
+        assert!([path.clone(), rotated(1)].iter().any(|file| {
+            std::fs::read_to_string(file)
+                .map(|content| content.contains("message 19"))
+                .unwrap_or(false)
+        }));
+        assert!(path.is_file());
+        assert!(rotated(1).is_file());
+        assert!(rotated(2).is_file());
+        assert!(!rotated(3).exists(), "only the configured amount of files is kept");
+        for file in [path.clone(), rotated(1), rotated(2)].iter() {
+            let len = std::fs::metadata(file).expect("exists").len();
+            assert!(len < 100 + 50, "files are rotated right after exceeding the limit");
+        }
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn write_errors_are_returned_when_shutting_down() {
+        let root = TreeOptions {
+            message_log: Some(MessageLog::to_writer(FailingWriter)),
+            ..TreeOptions::default()
+        }
+        .create();
+        root.add_child("task").info("lost");
+        let err = root.shutdown_message_log().expect_err("writing fails");
+        assert_eq!(err.to_string(), "disk full");
+    }
+}
+
+mod item {
+    use crate::{
+        progress::{key::Id, State, Step},
+        unit::display::Throughput,
+        Tree, TreeOptions,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn subscribers_receive_every_message_regardless_of_the_buffer_capacity() {
+        let root = TreeOptions {
+            message_buffer_capacity: 2,
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("task");
+        item.info("before subscribing");
+        let rx = root.subscribe_messages();
+        let dropped_rx = root.subscribe_messages();
+        drop(dropped_rx);
+        for id in 0..10 {
+            item.fail(format!("{}", id));
+        }
+
+        let mut buffered = Vec::new();
+        root.copy_messages(&mut buffered);
+        assert_eq!(buffered.len(), 2, "the buffer only keeps the latest messages");
+        assert_eq!(
+            rx.try_iter().map(|m| m.message).collect::<Vec<_>>(),
+            (0..10).map(|id| id.to_string()).collect::<Vec<_>>(),
+            "subscribers see all messages sent after subscribing, in order"
+        );
+        drop(root);
+        drop(item);
+        assert!(rx.recv().is_err(), "the channel closes once the tree is gone");
+    }
+
+    #[test]
+    fn messages_know_the_task_they_came_from() {
+        let root = Tree::new();
+        let mut first = root.add_child("clone");
+        let mut child = first.add_child("checkout");
+        let mut second = root.add_child("clone");
+        first.info("first");
+        child.info("child");
+        second.info("second");
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages[0].origin, messages[2].origin, "names can't tell them apart");
+        assert_ne!(messages[0].key, messages[2].key, "but keys can");
+        assert_eq!(messages[1].path, vec!["clone".to_string(), "checkout".into()]);
+        assert_eq!(messages[1].key.parent().as_ref(), Some(&messages[0].key));
+
+        let first_key = messages[0].key.clone();
+        root.copy_messages_from(&first_key, false, &mut messages);
+        assert_eq!(
+            messages.iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+            vec!["first"]
+        );
+        crate::Root::copy_messages_from(&root, &first_key, true, &mut messages);
+        assert_eq!(
+            messages.iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+            vec!["first", "child"],
+            "the subtree can be included"
+        );
+    }
+
+    #[test]
+    fn attributes_are_part_of_snapshots() {
+        let root = Tree::new();
+        let mut item = root.add_child("fetch");
+        item.set_attribute("url", "https://example.com/repo");
+        crate::Progress::set_attribute(&mut item, "worker", "3");
+        item.set_attribute("worker", "4");
+        assert_eq!(item.attribute("worker").as_deref(), Some("4"), "values are overwritten");
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0]
+                .1
+                .attributes
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("url", "https://example.com/repo"), ("worker", "4")]
+        );
+
+        assert_eq!(
+            item.remove_attribute("url").as_deref(),
+            Some("https://example.com/repo")
+        );
+        let generation = root.generation();
+        assert_eq!(item.remove_attribute("url"), None);
+        assert_eq!(
+            root.generation(),
+            generation,
+            "removing attributes which aren't set changes nothing"
+        );
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(snapshot[0].1.attributes.len(), 1);
+    }
+
+    #[test]
+    fn the_generation_changes_with_tasks_and_messages_only() {
+        let root = Tree::new();
+        let mut snapshot = Vec::new();
+        let mut generation = root.generation();
+        let mut assert_changed = |root: &Tree, changed: bool, what: &str| {
+            let next = root.generation();
+            assert_eq!(next != generation, changed, "{}", what);
+            assert!(next >= generation, "the generation never goes back");
+            generation = next;
+        };
+
+        let mut task = root.add_child("task");
+        assert_changed(&root, true, "adding a task");
+        task.init(Some(10), None);
+        assert_changed(&root, true, "initializing a task");
+        task.inc();
+        assert_changed(&root, true, "advancing a task");
+        root.sorted_snapshot(&mut snapshot);
+        assert_changed(&root, false, "taking a snapshot");
+        task.inc();
+        let clone = root.clone();
+        assert_eq!(
+            clone.generation(),
+            root.generation(),
+            "clones see the same generation, no matter which of them noticed the change"
+        );
+        assert_changed(&root, true, "advancing a task again");
+        task.info("hello");
+        assert_changed(&root, true, "sending a message");
+        task.set_name("renamed");
+        assert_changed(&root, true, "renaming a task");
+        task.halted("waiting", None);
+        assert_changed(&root, true, "changing the state of a task");
+        root.sorted_snapshot(&mut snapshot);
+        root.cancel(&snapshot[0].0);
+        assert_changed(&root, true, "cancelling a task");
+        drop(task);
+        assert_changed(&root, true, "removing a task");
+        root.sorted_snapshot(&mut snapshot);
+        assert_changed(&root, false, "taking another snapshot");
+    }
+
+    #[test]
+    fn cancellation_applies_to_a_task_and_everything_below_it() {
+        let root = Tree::new();
+        let mut parent = root.add_child("parent");
+        let mut child = parent.add_child("child");
+        let grandchild = child.add_child("grandchild");
+        let sibling = root.add_child("sibling");
+        assert!(!parent.is_cancelled());
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let parent_key = snapshot
+            .iter()
+            .find(|(_, task)| task.name == "parent")
+            .map(|(key, _)| key.clone())
+            .expect("parent present");
+        root.cancel(&parent_key);
+
+        assert!(parent.is_cancelled());
+        assert!(child.is_cancelled());
+        assert!(crate::Progress::is_cancelled(&grandchild));
+        assert!(!sibling.is_cancelled(), "tasks outside of the subtree are unaffected");
+        assert!(
+            parent.add_child("late child").is_cancelled(),
+            "children of cancelled tasks start out cancelled"
+        );
+    }
+
+    #[test]
+    fn parents_can_aggregate_the_progress_of_their_children() {
+        let root = Tree::new();
+        let mut stage = root.add_child("stage");
+        stage.set_aggregate_children(true);
+        let mut a = stage.add_child("a");
+        a.init(Some(10), None);
+        a.set(5);
+        let mut b = stage.add_child("b");
+        b.init(Some(30), None);
+        b.set(3);
+        let mut group = stage.add_child("group");
+        group.set_aggregate_children(true);
+        let mut c = group.add_child("c");
+        c.init(Some(4), None);
+        c.set(2);
+        let _unrelated = root.add_child("unrelated");
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let progress_of = |snapshot: &[(crate::progress::Key, crate::progress::Task)], name: &str| {
+            snapshot
+                .iter()
+                .find(|(_, task)| task.name == name)
+                .and_then(|(_, task)| task.progress.as_ref())
+                .map(|p| (p.step, p.done_at))
+        };
+        assert_eq!(progress_of(&snapshot, "group"), Some((2, Some(4))));
+        assert_eq!(
+            progress_of(&snapshot, "stage"),
+            Some((5 + 3 + 2, Some(10 + 30 + 4))),
+            "aggregates are aggregated in turn"
+        );
+        assert_eq!(progress_of(&snapshot, "unrelated"), None);
+
+        a.set_weight(Some(100));
+        b.set_weight(Some(100));
+        group.set_weight(Some(200));
+        b.finished();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            progress_of(&snapshot, "stage"),
+            Some((50 + 100 + 100, Some(400))),
+            "weights contribute fractions, and finished tasks count as done"
+        );
+
+        let mut unbounded = stage.add_child("unbounded");
+        unbounded.init(None, None);
+        unbounded.set(7);
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(progress_of(&snapshot, "stage"), Some((50 + 100 + 100 + 7, None)));
+    }
+
+    #[test]
+    fn aggregated_progress_saturates_instead_of_overflowing() {
+        let root = Tree::new();
+        let mut stage = root.add_child("stage");
+        stage.set_aggregate_children(true);
+        let mut children: Vec<_> = (0..2).map(|id| stage.add_child(format!("{}", id))).collect();
+        for child in &mut children {
+            child.init(Some(Step::MAX), None);
+            child.set(Step::MAX);
+        }
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.as_ref().expect("aggregated");
+        assert_eq!((progress.step, progress.done_at), (Step::MAX, Some(Step::MAX)));
+    }
+
+    #[test]
+    fn tasks_record_when_they_were_initialized_and_last_advanced() {
+        let root = Tree::new();
+        let mut item = root.add_child("task");
+        item.init(Some(10), None);
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.clone().expect("progress");
+        let initialized_at = progress.initialized_at.expect("set by init");
+        assert!(progress.last_advanced_at.is_none(), "no progress was made yet");
+
+        item.inc();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.clone().expect("progress");
+        assert!(progress.last_advanced_at.expect("set by inc") >= initialized_at);
+        assert!(progress.elapsed().is_some());
+        let throughput = Throughput::new(1, Duration::from_secs(1));
+        assert_eq!(progress.eta(Some(throughput)), Some(Duration::from_secs(9)));
+        assert_eq!(progress.eta(None), None, "without throughput, there is no estimate");
+
+        item.finished();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.as_ref().expect("progress");
+        assert_eq!(
+            progress.eta(Some(throughput)),
+            None,
+            "finished tasks have nothing left to do"
+        );
+        let elapsed = progress.elapsed().expect("known");
+        std::thread::sleep(Duration::from_millis(10));
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.progress.as_ref().and_then(|p| p.elapsed()),
+            Some(elapsed),
+            "the elapsed time of finished tasks doesn't change"
+        );
+    }
+
+    #[test]
+    fn terminal_states_are_kept_until_progress_is_made_again() {
+        let root = Tree::new();
+        let mut item = root.add_child("task");
+        item.init(Some(10), None);
+        item.set(10);
+        item.finished();
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let state = snapshot[0].1.progress.as_ref().expect("progress").state;
+        assert!(matches!(state, State::Finished(_)));
+        assert!(state.is_terminal());
+        assert!(state.ended_at().is_some());
+
+        item.failed();
+        root.sorted_snapshot(&mut snapshot);
+        assert!(matches!(
+            snapshot[0].1.progress.as_ref().expect("progress").state,
+            State::Failed(_)
+        ));
+
+        item.inc();
+        root.sorted_snapshot(&mut snapshot);
+        let state = snapshot[0].1.progress.as_ref().expect("progress").state;
+        assert!(matches!(state, State::Running));
+        assert!(state.ended_at().is_none());
+    }
+
+    #[test]
+    fn dropped_items_linger_with_their_final_state_if_configured() {
+        let root = TreeOptions {
+            linger: Some(Duration::from_millis(50)),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut lingering = root.add_child("lingering");
+        lingering.init(Some(10), None);
+        lingering.set(5);
+        let mut removed_right_away = root.add_child("removed right away");
+        removed_right_away.set_linger(None);
+        drop(lingering);
+        drop(removed_right_away);
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(snapshot.len(), 1, "only the lingering task remains");
+        let task = &snapshot[0].1;
+        assert_eq!(task.name, "lingering");
+        assert!(task.dropped_at.is_some(), "it is marked as dropped");
+        assert_eq!(
+            task.progress.as_ref().map(|p| p.step),
+            Some(5),
+            "its final state is retained"
+        );
+        assert_eq!(root.num_tasks(), 1, "lingering tasks are counted");
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(root.num_tasks(), 0, "tasks which lingered long enough aren't counted");
+        root.sorted_snapshot(&mut snapshot);
+        assert!(snapshot.is_empty(), "tasks are removed once they lingered long enough");
+        assert_eq!(root.num_tasks(), 0);
+    }
+
+    #[test]
+    fn expired_tasks_are_removed_when_lingering_items_are_dropped() {
+        let root = TreeOptions {
+            linger: Some(Duration::from_millis(10)),
+            ..TreeOptions::default()
+        }
+        .create();
+        drop(root.add_child("expires"));
+        std::thread::sleep(Duration::from_millis(20));
+        drop(root.add_child("lingers"));
+        assert_eq!(
+            root.inner.lock().tree.len(),
+            1,
+            "the expired task was removed without taking a snapshot"
+        );
+    }
+
+    #[test]
+    fn nesting_is_not_limited() {
+        let root = Tree::new();
+        let mut items = vec![root.add_child("level 1")];
+        for level in 2..=10 {
+            let child = items
+                .last_mut()
+                .expect("one item")
+                .add_child(format!("level {}", level));
+            items.push(child);
+        }
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot.iter().map(|(key, _)| key.level()).collect::<Vec<_>>(),
+            (1..=10).collect::<Vec<_>>(),
+            "every child is one level below its parent, and sorts after it"
+        );
+    }
+
+    #[test]
+    fn child_ids_are_unique_among_live_siblings_even_after_wrapping_around() {
+        let root = Tree::new();
+        let mut parent = root.add_child("parent");
+        let long_lived: Vec<_> = (0..3)
+            .map(|id| parent.add_child(format!("long-lived {}", id)))
+            .collect();
+        parent.highest_child_id = Id::MAX - 32_768;
+
+        for _ in 0..70_000 {
+            let short_lived = parent.add_child("short-lived");
+            assert!(
+                long_lived.iter().all(|item| item.key != short_lived.key),
+                "keys of live siblings are never handed out again"
+            );
+        }
+
+        for (id, item) in long_lived.iter().enumerate() {
+            assert_eq!(
+                item.name(),
+                Some(format!("long-lived {}", id)),
+                "dropping short-lived children never removes other siblings"
+            );
+        }
+        assert_eq!(root.num_tasks(), 1 + long_lived.len());
+    }
+}
+
+mod dyn_progress {
+    use crate::{Progress, Tree};
+
+    fn work<P: Progress>(progress: &mut P) -> P::SubProgress {
+        let mut child = progress.add_child("child");
+        child.init(Some(10), None);
+        child.inc_by(3);
+        child.info("halfway there");
+        child
+    }
+
+    #[test]
+    fn boxed_progress_can_be_used_wherever_a_progress_is_expected() {
+        let root = Tree::new();
+        let mut boxed: Box<dyn crate::DynProgress> = Box::new(root.add_child("parent"));
+        boxed.set_name("renamed");
+        assert_eq!(boxed.name().as_deref(), Some("renamed"));
+
+        let mut progress: Vec<Box<dyn crate::DynProgress>> = vec![boxed, Box::new(crate::progress::Discard)];
+        let children: Vec<_> = progress.iter_mut().map(work).collect();
+        assert_eq!(
+            children.iter().map(|c| c.step()).collect::<Vec<_>>(),
+            vec![3, 0],
+            "the tree tracks steps, but discarding progress doesn't"
+        );
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot.iter().map(|(_, t)| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["renamed", "child"]
+        );
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].path, vec!["renamed".to_string(), "child".into()]);
+    }
+}
+
+mod io {
+    use crate::{progress, Tree};
+    use std::io::{BufRead, Read, Write};
+
+    #[test]
+    fn readers_and_writers_count_the_bytes_passing_through() {
+        let root = Tree::new();
+        let mut reader = progress::Read::with_len(&b"hello\nworld\n"[..], root.add_child("read"), Some(12));
+        assert_eq!(reader.progress.max(), Some(12));
+        assert!(reader.progress.unit().is_some(), "a byte unit is set");
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(reader.progress.step(), Some(6), "buffered reads count consumed bytes");
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(reader.progress.step(), Some(12));
+
+        let mut writer = progress::Write::with_len(Vec::new(), root.add_child("write"), None);
+        writer.write_all(&rest).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.progress.max(), None, "progress is unbounded");
+        assert_eq!(writer.progress.step(), Some(6));
+        let (written, _progress) = writer.into_parts();
+        assert_eq!(written, b"world\n");
+    }
+
+    #[cfg(feature = "progress-io-async")]
+    #[test]
+    fn async_readers_and_writers_count_the_bytes_passing_through() {
+        use futures::{
+            executor::block_on,
+            io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, Cursor},
+        };
+        let root = Tree::new();
+        block_on(async {
+            let mut task = root.add_child("read");
+            task.init(None, None);
+            let mut reader = progress::Read::new(Cursor::new(b"hello\nworld\n".to_vec()), task);
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            assert_eq!(reader.progress.step(), Some(6));
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(reader.progress.step(), Some(12));
+
+            let mut writer = progress::Write::with_len(Cursor::new(Vec::new()), root.add_child("write"), Some(6));
+            writer.write_all(&rest).await.unwrap();
+            writer.close().await.unwrap();
+            assert_eq!(writer.progress.step(), Some(6));
+        });
+    }
+}
+
+mod iter {
+    use crate::{progress::IteratorExt, Tree};
+
+    #[test]
+    fn iterators_increment_progress_and_show_throughput_once_exhausted() {
+        let root = Tree::new();
+        let mut items = (0..5).with_progress(root.add_child("items"));
+        assert_eq!(items.progress.max(), Some(5), "the size hint is used as maximum");
+        assert_eq!(items.by_ref().take(3).count(), 3);
+        assert_eq!(items.progress.step(), Some(3));
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert!(
+            messages.is_empty(),
+            "throughput is shown only once the iterator is exhausted"
+        );
+
+        assert_eq!(items.by_ref().count(), 2);
+        assert_eq!(items.next(), None);
+        assert_eq!(items.progress.step(), Some(5));
+        root.copy_messages(&mut messages);
+        assert_eq!(messages.len(), 1, "throughput is shown once");
+        assert!(messages[0].message.starts_with("done 5 items"));
+
+        let unbounded = std::iter::repeat(())
+            .take_while(|_| false)
+            .with_progress(root.add_child("unbounded"));
+        assert_eq!(
+            unbounded.progress.max(),
+            None,
+            "without upper bound, progress is unbounded"
+        );
+    }
+
+    #[cfg(feature = "progress-stream")]
+    #[test]
+    fn streams_increment_progress_and_show_throughput_once_exhausted() {
+        use crate::progress::StreamExt as _;
+        use futures::{executor::block_on, stream, StreamExt};
+
+        let root = Tree::new();
+        let mut items = stream::iter(0..5).with_progress(root.add_child("items"));
+        assert_eq!(items.progress.max(), Some(5));
+        assert_eq!(block_on(items.by_ref().collect::<Vec<_>>()), vec![0, 1, 2, 3, 4]);
+        assert_eq!(items.progress.step(), Some(5));
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages.len(), 1);
+    }
+}
+
+#[cfg(feature = "progress-tree-tracing")]
+mod tracing {
+    use crate::{messages::MessageLevel, tree::TracingLayer, Tree};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn names(root: &Tree) -> Vec<(usize, String)> {
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        snapshot
+            .into_iter()
+            .map(|(key, task)| (key.level(), task.name))
+            .collect()
+    }
+
+    #[test]
+    fn spans_become_tasks_and_events_become_messages() {
+        let root = Tree::new();
+        let subscriber = tracing_subscriber::registry().with(TracingLayer::new(root.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!(
+                "clone",
+                progress.max = 10u64,
+                progress.step = tracing::field::Empty,
+                url = "https://example.com"
+            );
+            let _outer = outer.enter();
+            assert_eq!(names(&root), vec![(1, "clone".to_string())]);
+            {
+                let inner = tracing::info_span!("ignored", progress.name = "checkout");
+                assert_eq!(names(&root).len(), 1, "spans become tasks only once entered");
+                let _inner = inner.enter();
+                assert_eq!(
+                    names(&root),
+                    vec![(1, "clone".to_string()), (2, "checkout".into())],
+                    "parent spans are parent tasks"
+                );
+                tracing::warn!(files = 3, "slow");
+            }
+            assert_eq!(names(&root).len(), 1, "tasks are removed once their span closes");
+
+            outer.record("progress.step", 4u64);
+            tracing::error!("broken");
+
+            let mut snapshot = Vec::new();
+            root.sorted_snapshot(&mut snapshot);
+            let task = &snapshot[0].1;
+            assert_eq!(
+                task.progress.as_ref().map(|p| (p.step, p.done_at)),
+                Some((4, Some(10))),
+                "progress fields initialize and advance the task"
+            );
+            assert_eq!(
+                task.attributes.get("url").map(String::as_str),
+                Some("https://example.com"),
+                "other fields become attributes"
+            );
+        });
+        tracing::subscriber::with_default(
+            tracing_subscriber::registry().with(TracingLayer::new(root.clone())),
+            || tracing::info!("outside of any span"),
+        );
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| (m.level, m.path.join("/"), m.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (MessageLevel::Warning, "clone/checkout".to_string(), "slow files=3"),
+                (MessageLevel::Failure, "clone".into(), "broken"),
+                (MessageLevel::Info, "".into(), "outside of any span"),
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_entered_on_many_threads_at_once_become_a_single_task() {
+        let root = Tree::new();
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(TracingLayer::new(root.clone())));
+        let span = tracing::dispatcher::with_default(&dispatch, || tracing::info_span!("shared"));
+        let barrier = std::sync::Barrier::new(8);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        barrier.wait();
+                        let _entered = span.enter();
+                        barrier.wait();
+                    })
+                });
+            }
+        });
+        assert_eq!(names(&root), vec![(1, "shared".to_string())]);
+    }
+}
+
+#[cfg(feature = "progress-tree-log")]
+mod log_capture {
+    use crate::{messages::MessageLevel, tree::LogCapture, Tree};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    const TARGET: &str = "capture-test";
+
+    struct Fallback(Arc<Mutex<Vec<String>>>);
+
+    impl log::Log for Fallback {
+        fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record<'_>) {
+            if record.target() == TARGET {
+                self.0.lock().push(record.args().to_string());
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn log_records_are_captured_while_the_capture_exists_and_passed_to_the_fallback_otherwise() {
+        let fallback = Arc::new(Mutex::new(Vec::new()));
+        LogCapture::install(Some(Box::new(Fallback(fallback.clone()))), log::LevelFilter::Debug)
+            .expect("no other test installs a logger");
+
+        let root = Tree::new();
+        log::info!(target: TARGET, "before");
+        {
+            let _capture = root.capture_log();
+            log::warn!(target: TARGET, "during");
+            log::debug!(target: TARGET, "details");
+            log::trace!(target: TARGET, "filtered by level");
+            root.add_child("task").info("from the tree");
+        }
+        log::error!(target: TARGET, "after");
+
+        let (first, second) = (Tree::new(), Tree::new());
+        let first_capture = first.capture_log();
+        let second_capture = second.capture_log();
+        log::info!(target: TARGET, "to the second");
+        drop(first_capture);
+        log::info!(target: TARGET, "still to the second");
+        drop(second_capture);
+        log::info!(target: TARGET, "to the fallback");
+        for (tree, expected) in [
+            (&first, vec![]),
+            (&second, vec!["to the second", "still to the second"]),
+        ]
+        .iter()
+        {
+            let mut messages = Vec::new();
+            tree.copy_messages(&mut messages);
+            assert_eq!(
+                messages.iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+                *expected,
+                "captures can end in any order"
+            );
+        }
+
         assert_eq!(
-            Key::adjacency(&entries, 1),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
+            *fallback.lock(),
+            vec!["before".to_string(), "after".into(), "to the fallback".into()]
         );
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
         assert_eq!(
-            Key::adjacency(&entries, 2),
-            Adjacency(Above, NotFound, NotFound, NotFound)
+            messages
+                .iter()
+                .map(|m| (m.level, m.origin.as_str(), m.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (MessageLevel::Warning, TARGET, "during"),
+                (MessageLevel::Debug, TARGET, "details"),
+                (MessageLevel::Info, "task", "from the tree"),
+            ],
+            "messages of the tree itself aren't captured a second time"
         );
     }
 }
@@ -0,0 +1,214 @@
+use crate::{
+    messages::MessageLevel,
+    progress::Step,
+    tree::{Item, Root},
+};
+use std::fmt::{self, Write};
+use tracing_core::{
+    field::{Field, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+/// The span field to set the name of its task with, instead of the name of the span.
+pub const NAME_FIELD: &str = "progress.name";
+/// The span field to set the current step of its task with, initializing the task if needed.
+pub const STEP_FIELD: &str = "progress.step";
+/// The span field to set the maximum step of its task with, initializing the task if needed.
+pub const MAX_FIELD: &str = "progress.max";
+
+/// A `tracing_subscriber::Layer` mirroring spans into a progress tree.
+///
+/// Each span turns into a task the first time it is entered, as child of the task of its closest parent span that has one,
+/// or at the top of the tree. The task is removed once the span is closed.
+/// Span fields become attributes of the task, unless they are one of [`NAME_FIELD`](./tracing/constant.NAME_FIELD.html),
+/// [`STEP_FIELD`](./tracing/constant.STEP_FIELD.html) or [`MAX_FIELD`](./tracing/constant.MAX_FIELD.html),
+/// which control the name and the progress of the task instead. Like all fields, they can only be recorded later
+/// if they are declared when creating the span, possibly as `tracing::field::Empty`.
+///
+/// Events turn into messages of the task of their closest span, with `TRACE` and `DEBUG` mapping to `MessageLevel::Debug`,
+/// `INFO` to `MessageLevel::Info`, `WARN` to `MessageLevel::Warning` and `ERROR` to `MessageLevel::Failure`.
+///
+/// ```rust
+/// use tracing_subscriber::layer::SubscriberExt;
+/// let tree = prodash::Tree::new();
+/// let subscriber = tracing_subscriber::registry().with(prodash::tree::TracingLayer::new(tree.clone()));
+/// ```
+pub struct TracingLayer {
+    root: Root,
+}
+
+impl TracingLayer {
+    /// Create a new instance adding tasks and messages to the given `root`.
+    pub fn new(root: Root) -> Self {
+        TracingLayer { root }
+    }
+}
+
+/// The fields of a span which weren't applied to its task yet.
+#[derive(Default)]
+struct Fields {
+    name: Option<String>,
+    step: Option<Step>,
+    max: Option<Step>,
+    attributes: Vec<(String, String)>,
+}
+
+impl Fields {
+    fn apply(&mut self, item: &mut Item) {
+        if let Some(name) = self.name.take() {
+            item.set_name(name);
+        }
+        let step = self.step.take();
+        let max = self.max.take();
+        if max.is_some() || (step.is_some() && item.step().is_none()) {
+            let current_step = item.step();
+            item.init(max.or_else(|| item.max()), item.unit());
+            if let Some(step) = step.or(current_step) {
+                item.set(step);
+            }
+        } else if let Some(step) = step {
+            item.set(step);
+        }
+        for (key, value) in self.attributes.drain(..) {
+            item.set_attribute(key, value);
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        match field.name() {
+            STEP_FIELD | MAX_FIELD if value >= 0 => self.record_u64(field, value as u64),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            STEP_FIELD => self.step = Some(value as Step),
+            MAX_FIELD => self.max = Some(value as Step),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            NAME_FIELD => self.name = Some(value.into()),
+            name => self.attributes.push((name.into(), value.into())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            NAME_FIELD => self.name = Some(format!("{:?}", value)),
+            name => self.attributes.push((name.into(), format!("{:?}", value))),
+        }
+    }
+}
+
+/// The text of an event, made of its message followed by all other fields.
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.insert_str(0, value);
+        } else {
+            write!(self.0, " {}={}", field.name(), value).ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{:?}", value));
+        } else {
+            write!(self.0, " {}={:?}", field.name(), value).ok();
+        }
+    }
+}
+
+fn message_level(level: &Level) -> MessageLevel {
+    match *level {
+        Level::TRACE | Level::DEBUG => MessageLevel::Debug,
+        Level::INFO => MessageLevel::Info,
+        Level::WARN => MessageLevel::Warning,
+        Level::ERROR => MessageLevel::Failure,
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = Fields::default();
+            attrs.record(&mut fields);
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            let mut fields = extensions.remove::<Fields>().unwrap_or_default();
+            values.record(&mut fields);
+            if let Some(item) = extensions.get_mut::<Item>() {
+                fields.apply(item);
+            }
+            extensions.insert(fields);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        // Holding the lock until the item is inserted makes sure that only one item is created if the span
+        // is entered on multiple threads at once.
+        let mut extensions = span.extensions_mut();
+        if extensions.get_mut::<Item>().is_some() {
+            return;
+        }
+        let name = span.metadata().name();
+        let parent_item = span.scope().skip(1).find_map(|parent| {
+            parent
+                .extensions_mut()
+                .get_mut::<Item>()
+                .map(|item| item.add_child(name))
+        });
+        let mut item = parent_item.unwrap_or_else(|| self.root.add_child(name));
+
+        if let Some(fields) = extensions.get_mut::<Fields>() {
+            fields.apply(&mut item);
+        }
+        extensions.insert(item);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut message = Message::default();
+        event.record(&mut message);
+        let level = message_level(event.metadata().level());
+
+        let span_with_item = ctx
+            .event_span(event)
+            .and_then(|span| span.scope().find(|span| span.extensions().get::<Item>().is_some()));
+        match span_with_item {
+            Some(span) => {
+                if let Some(item) = span.extensions_mut().get_mut::<Item>() {
+                    item.message(level, message.0);
+                }
+            }
+            None => self.root.inner.lock().message(level, message.0),
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            span.extensions_mut().remove::<Item>();
+        }
+    }
+}
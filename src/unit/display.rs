@@ -5,6 +5,7 @@ use crate::{
 use std::fmt::{self, Write};
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Location {
     BeforeValue,
     AfterUnit,
@@ -23,13 +24,29 @@ impl Throughput {
             timespan,
         }
     }
+
+    /// Estimate the time it takes to get from `current_value` to `upper_bound` at this throughput.
+    ///
+    /// Returns `None` if no progress is made, as the task would never be done, or if the estimate is too large to be represented.
+    pub fn eta(&self, current_value: Step, upper_bound: Step) -> Option<std::time::Duration> {
+        if self.value_change_in_timespan == 0 {
+            return None;
+        }
+        let remaining = upper_bound.saturating_sub(current_value);
+        std::time::Duration::try_from_secs_f64(
+            remaining as f64 * self.timespan.as_secs_f64() / self.value_change_in_timespan as f64,
+        )
+        .ok()
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mode {
     location: Location,
     percent: bool,
     throughput: bool,
+    eta: bool,
 }
 
 impl Mode {
@@ -48,6 +65,19 @@ impl Mode {
             None
         }
     }
+
+    #[cfg(any(feature = "render-tui", feature = "render-line"))]
+    pub(crate) fn shows_eta(&self) -> bool {
+        self.eta
+    }
+
+    fn eta_location(&self) -> Option<Location> {
+        if self.eta {
+            Some(self.location)
+        } else {
+            None
+        }
+    }
 }
 
 /// initialization and modification
@@ -56,6 +86,7 @@ impl Mode {
         Mode {
             percent: true,
             throughput: false,
+            eta: false,
             location: Location::AfterUnit,
         }
     }
@@ -63,6 +94,7 @@ impl Mode {
         Mode {
             percent: false,
             throughput: true,
+            eta: false,
             location: Location::AfterUnit,
         }
     }
@@ -74,6 +106,11 @@ impl Mode {
         self.throughput = true;
         self
     }
+    /// Show the estimated time until the upper bound is reached, which requires throughput information.
+    pub fn and_eta(mut self) -> Self {
+        self.eta = true;
+        self
+    }
     pub fn show_before_value(mut self) -> Self {
         self.location = Location::BeforeValue;
         self
@@ -137,6 +174,11 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
             mode.and_then(|m| m.throughput_location())
                 .map(|location| (location, throughput))
         });
+        let eta_and_location = self
+            .throughput
+            .zip(self.upper_bound)
+            .and_then(|(throughput, upper)| throughput.eta(self.current_value, upper))
+            .and_then(|eta| mode.and_then(|m| m.eta_location()).map(|location| (location, eta)));
         if self.display.values() {
             if let Some((Location::BeforeValue, fraction)) = percent_location_and_fraction {
                 unit.display_percentage(f, fraction)?;
@@ -146,6 +188,10 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
                 unit.display_throughput(f, throughput)?;
                 f.write_char(' ')?;
             }
+            if let Some((Location::BeforeValue, eta)) = eta_and_location {
+                unit.display_eta(f, eta)?;
+                f.write_char(' ')?;
+            }
             unit.display_current_value(f, self.current_value, self.upper_bound)?;
             if let Some(upper) = self.upper_bound {
                 unit.separator(f, self.current_value, self.upper_bound)?;
@@ -171,6 +217,10 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
                 f.write_char(' ')?;
                 unit.display_throughput(f, throughput)?;
             }
+            if let Some((Location::AfterUnit, eta)) = eta_and_location {
+                f.write_char(' ')?;
+                unit.display_eta(f, eta)?;
+            }
         }
         Ok(())
     }
@@ -25,6 +25,9 @@ pub use traits::DisplayValue;
 
 pub mod display;
 
+#[cfg(feature = "serde")]
+pub(crate) mod serialize;
+
 #[derive(Debug, Clone)]
 pub struct Unit {
     kind: Kind,
@@ -100,6 +103,12 @@ impl Unit {
             Kind::Dynamic(ref unit) => unit.deref(),
         }
     }
+
+    /// Returns true if the estimated time remaining is part of the display, so renderers don't have to show it.
+    #[cfg(any(feature = "render-tui", feature = "render-line"))]
+    pub(crate) fn shows_eta(&self) -> bool {
+        matches!(self.mode, Some(mode) if mode.shows_eta())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,73 @@
+use crate::unit::{display, Kind, Unit};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeSet, sync::Mutex};
+
+/// How units look like when serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Unit")]
+struct Repr {
+    /// The label as displayed, for dynamic units as displayed next to a value of zero.
+    label: String,
+    mode: Option<display::Mode>,
+}
+
+/// Units are serialized by their label, and always deserialize into units with a label,
+/// as the behaviour of dynamic units can't be restored.
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let label = match &self.kind {
+            Kind::Label(label) => label.to_string(),
+            Kind::Dynamic(unit) => {
+                let mut label = String::new();
+                unit.display_unit(&mut label, 0)
+                    .map_err(|_| serde::ser::Error::custom("unit could not be displayed"))?;
+                label
+            }
+        };
+        Repr { label, mode: self.mode }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Repr { label, mode } = Repr::deserialize(deserializer)?;
+        Ok(Unit {
+            kind: Kind::Label(intern(label)),
+            mode,
+        })
+    }
+}
+
+/// The maximum amount of distinct strings to leak with [`intern()`].
+const MAX_INTERNED: usize = 1024;
+/// The maximum length in bytes of a string to leak with [`intern()`].
+const MAX_INTERNED_LEN: usize = 256;
+/// What [`intern()`] returns once it can't leak any more strings.
+const PLACEHOLDER: &str = "…";
+
+/// Leak `s` unless an equal string was leaked before, which is returned instead.
+///
+/// The leaked memory is never freed, which is why strings are truncated to `MAX_INTERNED_LEN` bytes,
+/// and why new strings are replaced by `PLACEHOLDER` once `MAX_INTERNED` strings were leaked.
+/// That way, input from untrusted sources can't make memory usage grow without bounds, while
+/// long-running programs can keep deserializing.
+pub(crate) fn intern(mut s: String) -> &'static str {
+    static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    if s.len() > MAX_INTERNED_LEN {
+        let mut end = MAX_INTERNED_LEN;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+    }
+    let mut interned = INTERNED.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(existing) = interned.get(s.as_str()) {
+        return existing;
+    }
+    if interned.len() >= MAX_INTERNED {
+        return PLACEHOLDER;
+    }
+    let leaked: &'static str = Box::leak(s.into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
@@ -188,6 +188,76 @@ mod label {
             );
         }
 
+        #[test]
+        fn display_eta_for_bounded_values_with_throughput() {
+            let unit = unit::label_and_mode("items", display::Mode::with_percentage().and_eta());
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(100, None, display::Throughput::new(10, time::Duration::from_secs(1)))
+                ),
+                "100 items",
+                "unbounded values have no ETA"
+            );
+            assert_eq!(
+                format!("{}", unit.display(100, Some(200), None)),
+                "100/200 items [50%]",
+                "without throughput, there is no ETA"
+            );
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        100,
+                        Some(200),
+                        display::Throughput::new(0, time::Duration::from_secs(1))
+                    )
+                ),
+                "100/200 items [50%]",
+                "without any progress, there is no ETA either"
+            );
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        100,
+                        Some(200),
+                        display::Throughput::new(10, time::Duration::from_secs(1))
+                    )
+                ),
+                "100/200 items [50%] [ETA 10s]"
+            );
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        100,
+                        Some(10_000),
+                        display::Throughput::new(1, time::Duration::from_secs(1))
+                    )
+                ),
+                "100/10000 items [1%] [ETA 2h45m]",
+                "it uses the two biggest units"
+            );
+            let unit = unit::label_and_mode("items", display::Mode::with_throughput().and_eta().show_before_value());
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        50,
+                        Some(200),
+                        display::Throughput::new(1, time::Duration::from_millis(500))
+                    )
+                ),
+                "|1/500ms| [ETA 1m15s] 50/200 items"
+            );
+            assert_eq!(
+                display::Throughput::new(1, time::Duration::from_secs(u64::MAX)).eta(0, usize::MAX),
+                None,
+                "estimates too large for a duration are unknown"
+            );
+        }
+
         #[test]
         fn display_current_value_no_upper_bound_shows_no_percentage() {
             assert_eq!(
@@ -246,10 +316,43 @@ mod size {
 
     #[test]
     fn of_mode() {
-        assert_eq!(size_of::<display::Mode>(), 3);
+        assert_eq!(size_of::<display::Mode>(), 4);
     }
     #[test]
     fn of_unit() {
         assert_eq!(size_of::<Unit>(), 32);
     }
 }
+
+#[cfg(all(feature = "serde", feature = "unit-human"))]
+mod serialization {
+    use crate::unit::{self, display, human, Human, Unit};
+
+    #[test]
+    fn dynamic_units_are_restored_as_labels_with_the_same_mode() {
+        let unit = unit::dynamic_and_mode(
+            Human::new(human::Formatter::new(), "objects"),
+            display::Mode::with_percentage(),
+        );
+        let json = serde_json::to_string(&unit).unwrap();
+        assert_eq!(
+            json,
+            r#"{"label":"objects","mode":{"location":"AfterUnit","percent":true,"throughput":false,"eta":false}}"#
+        );
+        let restored: Unit = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{}", restored.display(5, Some(10), None)), "5/10 objects [50%]");
+    }
+
+    #[test]
+    fn overly_long_labels_are_truncated_as_they_are_leaked() {
+        let json = serde_json::to_string(&unit::label("x"))
+            .unwrap()
+            .replace('x', &"ä".repeat(200));
+        let restored: Unit = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            format!("{}", restored.display(1, None, None)),
+            format!("1 {}", "ä".repeat(128)),
+            "labels are cut at a character boundary"
+        );
+    }
+}
@@ -26,6 +26,23 @@ pub trait DisplayValue {
         }?;
         w.write_fmt(format_args!("{}|", unit))
     }
+    fn display_eta(&self, w: &mut dyn fmt::Write, eta: std::time::Duration) -> fmt::Result {
+        const HOUR_IN_SECS: u64 = 60 * 60;
+        const MINUTES_IN_SECS: u64 = 60;
+        let secs = eta.as_secs();
+        let (h, m, s) = (
+            secs / HOUR_IN_SECS,
+            secs % HOUR_IN_SECS / MINUTES_IN_SECS,
+            secs % MINUTES_IN_SECS,
+        );
+        if h > 0 {
+            w.write_fmt(format_args!("[ETA {}h{:02}m]", h, m))
+        } else if m > 0 {
+            w.write_fmt(format_args!("[ETA {}m{:02}s]", m, s))
+        } else {
+            w.write_fmt(format_args!("[ETA {}s]", s))
+        }
+    }
     fn fraction_and_time_unit(&self, timespan: std::time::Duration) -> (Option<f64>, &'static str) {
         fn skip_one(v: f64) -> Option<f64> {
             if (v - 1.0).abs() < f64::EPSILON {